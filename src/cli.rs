@@ -0,0 +1,619 @@
+//! Command line interface with a subcommand for each stage of the pipeline.
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+
+use config::Config;
+use run::Run;
+use senses::auditory::{
+    detect_activity, find_spectral_objects, frame_window, frame_windows, load_wav, mfcc, resample,
+    save_events, save_spectral_objects, save_window_features, segment_events, signal_statistics,
+    stft, track_pitch, AuditoryConfig, AuditoryObject, Window, WindowFeatures,
+};
+use senses::visual::{
+    identify_objects, CropMode, HeatSource, Point as VisualPoint, SplittingStrategy,
+    StructuringElement, VisualConfig,
+};
+use senses::{Clip, ClipIssue, Dataset, MediaKind, MediaSource};
+use som::{Neuron, Point, SelfOrganizingMap};
+
+type CliResult = Result<(), Box<dyn Error>>;
+
+/// How many times is the map trained on all the objects of a clip.
+const TRAIN_EPOCHS: usize = 10;
+
+/// Builds the parser of the command line arguments.
+pub fn app<'a, 'b>() -> App<'a, 'b> {
+    App::new("semantic-brain-model")
+        .about("Connects visual and auditory information from a video.")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .args(&config_args())
+        .subcommand(
+            SubCommand::with_name("objects")
+                .about("Extracts objects from a frame or a directory of frames")
+                .arg(path_arg("input", "Frame or directory of frames"))
+                .arg(output_arg())
+                .args(&visual_args()),
+        )
+        .subcommand(
+            SubCommand::with_name("sound")
+                .about("Analyses a WAV")
+                .arg(path_arg("input", "WAV to analyse"))
+                .arg(output_arg())
+                .args(&auditory_args()),
+        )
+        .subcommand(
+            SubCommand::with_name("train")
+                .about("Trains a map on a clip")
+                .arg(path_arg(
+                    "clip",
+                    "Clip directory, or id of a clip in the data root",
+                ))
+                .arg(output_arg())
+                .args(&visual_args())
+                .args(&auditory_args()),
+        )
+        .subcommand(
+            SubCommand::with_name("clips")
+                .about("Lists the clips in the data root and checks them"),
+        )
+        .subcommand(
+            SubCommand::with_name("inspect")
+                .about("Prints a saved map")
+                .arg(path_arg("map", "File the map was saved to")),
+        )
+}
+
+/// Runs the subcommand given on the command line.
+pub fn run(matches: &ArgMatches) -> CliResult {
+    let config = load_config(matches)?;
+
+    match matches.subcommand() {
+        ("objects", Some(args)) => objects(args, &config),
+        ("sound", Some(args)) => analyse_sound(args, &config),
+        ("train", Some(args)) => train(args, &config),
+        ("clips", Some(_)) => clips(&config),
+        ("inspect", Some(args)) => inspect(args),
+        _ => Ok(()),
+    }
+}
+
+/// Identifies objects in each frame. Frames of a directory are saved into
+/// directories named after them. Frames that cannot be processed are skipped.
+fn objects(args: &ArgMatches, config: &Config) -> CliResult {
+    let source = MediaSource::from_path(args.value_of("input").unwrap())?;
+    let visual = visual_config(args, config)?;
+
+    let mut run = start_run(args, config, "objects", &source)?;
+    run.record("visual", format!("{:?}", visual))?;
+
+    for frame in source.frames()? {
+        let frame_output = match (source.kind(), frame.path().file_stem()) {
+            (MediaKind::FrameSequence, Some(name)) => run.path(&name.to_string_lossy()),
+            _ => run.directory().to_path_buf(),
+        };
+
+        fs::create_dir_all(&frame_output)?;
+
+        match identify_objects(&frame, &visual, Some(&frame_output)) {
+            Ok(objects) => {
+                let parts: usize = objects.iter().map(|object| object.leaves().len()).sum();
+                println!("{}: {} objects in {} parts", frame, objects.len(), parts)
+            }
+            Err(error) => eprintln!("Skipping {}: {}", frame, error),
+        }
+    }
+
+    print_run(&run);
+
+    Ok(())
+}
+
+fn analyse_sound(args: &ArgMatches, config: &Config) -> CliResult {
+    let source = MediaSource::from_path(args.value_of("input").unwrap())?;
+    let auditory = auditory_config(args)?;
+
+    let mut run = start_run(args, config, "sound", &source)?;
+    run.record("auditory", format!("{:?}", auditory))?;
+
+    // Statistics are of the WAV as it is, before it is resampled.
+    let wav = load_wav(&source)?;
+    let statistics = signal_statistics(&wav);
+    statistics.save(run.path("statistics.json"))?;
+    for issue in statistics.issues() {
+        eprintln!("Warning: {}: {}", source, issue);
+    }
+
+    let signal = resample(&wav, config.sample_rate);
+    let spectrogram = stft(&signal, &auditory);
+    spectrogram.save(run.path("spectrogram.png"))?;
+    mfcc(&spectrogram, &auditory).save(run.path("mfcc.csv"))?;
+    track_pitch(&signal, &auditory).save(run.path("pitch.csv"))?;
+
+    let activity = detect_activity(&signal, &auditory);
+    activity.save(run.path("activity.csv"))?;
+
+    // Onsets in the room noise between sounds are not events.
+    let events: Vec<AuditoryObject> = segment_events(&spectrogram, &auditory)
+        .into_iter()
+        .filter(|event| activity.is_active((event.start + event.end) / 2_f64))
+        .collect();
+    save_events(&events, run.path("events.csv"))?;
+
+    // Salient blobs of the spectrogram are found by the visual pipeline.
+    let visual = VisualConfig {
+        cell_size: config.cell_size,
+        ..VisualConfig::default()
+    };
+    let objects = find_spectral_objects(&spectrogram, &visual);
+    save_spectral_objects(&objects, run.path("objects.csv"))?;
+
+    // Audio of each video frame, so that it can be matched with the frames.
+    let features: Vec<WindowFeatures> = frame_windows(&signal, config.fps, auditory.frame_overlap)
+        .iter()
+        .map(|window| window.features(&auditory))
+        .collect();
+    save_window_features(&features, run.path("frames.csv"))?;
+
+    println!(
+        "{}: {} events, {} objects, {} frames, {:.0}% active",
+        source,
+        events.len(),
+        objects.len(),
+        features.len(),
+        activity.active_share() * 100_f64
+    );
+
+    print_run(&run);
+
+    Ok(())
+}
+
+/// Trains the map on all percepts of a clip. Each object found in the frames
+/// becomes a neuron placed at the centre of the object, with the frames laid
+/// side by side in the order they were shown. Its weights start at the shape
+/// of the object followed by the mean MFCCs of the audio of its frame. The map
+/// is then trained on the percepts of all the objects.
+fn train(args: &ArgMatches, config: &Config) -> CliResult {
+    let clip = open_clip(args.value_of("clip").unwrap(), config)?;
+    let visual = visual_config(args, config)?;
+    let auditory = auditory_config(args)?;
+
+    let (warnings, errors): (Vec<ClipIssue>, Vec<ClipIssue>) = clip
+        .validate(config.sample_rate)
+        .into_iter()
+        .partition(|issue| issue.is_warning());
+
+    for warning in warnings.iter() {
+        eprintln!("Warning: {}: {}", clip.id(), warning);
+    }
+
+    if !errors.is_empty() {
+        let errors: Vec<String> = errors.iter().map(|issue| issue.to_string()).collect();
+
+        return Err(format!("Clip {} cannot be used: {}", clip.id(), errors.join(", ")).into());
+    }
+
+    // Validation makes sure that there is a single readable WAV.
+    let audio = clip.audio().unwrap();
+    let signal = resample(&load_wav(&audio)?, config.sample_rate);
+
+    // Centre of each object in cells, the index of its frame and its percept.
+    let mut percepts: Vec<(f64, f64, usize, Vec<f32>)> = Vec::new();
+
+    for (index, frame) in clip.frames(config.fps).iter().enumerate() {
+        let mut objects = match identify_objects(frame, &visual, None) {
+            Ok(objects) => objects,
+            Err(error) => {
+                eprintln!("Skipping {}: {}", frame, error);
+                continue;
+            }
+        };
+
+        let window = frame_window(&signal, config.fps, index, auditory.frame_overlap);
+        let mfcc = window.features(&auditory).mfcc;
+
+        for object in objects.iter_mut().flat_map(|object| object.leaves_mut()) {
+            let reference = object.reference;
+
+            if let Some((lower, higher)) = object.size() {
+                let x = reference.x as f64 + (lower.x + higher.x) as f64 / 2_f64;
+                let y = reference.y as f64 + (lower.y + higher.y) as f64 / 2_f64;
+
+                let mut features = shape_features(lower, higher, object.points.len());
+                features.extend(mfcc.iter());
+
+                percepts.push((x, y, index, features));
+            }
+        }
+    }
+
+    // Frames are laid side by side, so that an object which stays in place
+    // does not take the position of its neuron from an earlier frame.
+    let stride = percepts.iter().fold(0_f64, |max, (x, _, _, _)| max.max(*x)) + 1_f64;
+    let items: Vec<(Point, Neuron)> = percepts
+        .iter()
+        .map(|(x, y, frame, features)| {
+            let point = Point::new(x + *frame as f64 * stride, *y);
+
+            (point, Neuron::new(features.clone()))
+        })
+        .collect();
+    let inputs: Vec<Vec<f32>> = percepts
+        .into_iter()
+        .map(|(_, _, _, features)| features)
+        .collect();
+
+    if items.len() < 4 {
+        return Err(format!("Clip {} has fewer than 4 objects to train on", clip.id()).into());
+    }
+
+    let mut run = start_run(args, config, "train", &clip.video())?;
+    run.record("visual", format!("{:?}", visual))?;
+    run.record("auditory", format!("{:?}", auditory))?;
+    run.record("epochs", TRAIN_EPOCHS)?;
+
+    // Objects whose centres fall onto the same point share a neuron.
+    let mut map = SelfOrganizingMap::new(items);
+    run.record("items", map.items())?;
+    map.fit(&inputs, TRAIN_EPOCHS);
+    map.save(run.path("map.txt"))?;
+
+    print_run(&run);
+
+    Ok(())
+}
+
+/// Shape of an object given by its bounding box in cells: its width, its
+/// height and the share of the box its cells cover.
+fn shape_features(lower: VisualPoint, higher: VisualPoint, cells: usize) -> Vec<f32> {
+    let width = (higher.x - lower.x + 1) as f32;
+    let height = (higher.y - lower.y + 1) as f32;
+
+    vec![width, height, cells as f32 / (width * height)]
+}
+
+/// Prints each clip of the data root with the number of its frames, or with
+/// what is missing or malformed in it.
+fn clips(config: &Config) -> CliResult {
+    let dataset = Dataset::open(&config.data_root)?;
+    let clips = dataset.clips()?;
+
+    println!("{}: {} clips", dataset.root().display(), clips.len());
+
+    for clip in clips.iter() {
+        let issues: Vec<String> = clip
+            .validate(config.sample_rate)
+            .iter()
+            .map(|issue| issue.to_string())
+            .collect();
+
+        if issues.is_empty() {
+            println!("{}: {} frames", clip.id(), clip.frames(config.fps).len());
+        } else {
+            println!("{}: {}", clip.id(), issues.join(", "));
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints the statistics of a saved map.
+fn inspect(args: &ArgMatches) -> CliResult {
+    let map = fs::read_to_string(args.value_of("map").unwrap())?;
+
+    for line in map.lines() {
+        let mut parts = line.splitn(2, '=');
+
+        match (parts.next(), parts.next()) {
+            (Some(key), Some(value)) => println!("{:<18}{}", key, value),
+            _ => return Err(format!("Malformed line in map: {}", line).into()),
+        }
+    }
+
+    Ok(())
+}
+
+/// Loads the configuration and overrides it with the flags given on the
+/// command line.
+fn load_config(matches: &ArgMatches) -> Result<Config, Box<dyn Error>> {
+    let args = match matches.subcommand() {
+        (_, Some(args)) => args,
+        _ => matches,
+    };
+
+    // Flags are named after the keys, e.g. CELL_SIZE is given as --cell-size.
+    let config = Config::load()?.merge(|key| {
+        args.value_of(key.to_lowercase().replace('_', "-"))
+            .map(String::from)
+    })?;
+
+    Ok(config)
+}
+
+/// Opens a clip from its directory. If there is no such directory, the clip is
+/// looked up by its id in the data root.
+fn open_clip(clip: &str, config: &Config) -> Result<Clip, Box<dyn Error>> {
+    if Path::new(clip).is_dir() {
+        return Ok(Clip::open(clip)?);
+    }
+
+    Ok(Dataset::open(&config.data_root)?.clip(clip)?)
+}
+
+/// Tells where the results of a finished run are.
+fn print_run(run: &Run) {
+    println!(
+        "Results of run {} are in {}",
+        run.id(),
+        run.directory().display()
+    );
+}
+
+/// Creates the directory the run saves its results into and records the
+/// subcommand and its input. The directory is given by the output flag,
+/// otherwise a new one is made in the output root.
+fn start_run(
+    args: &ArgMatches,
+    config: &Config,
+    command: &str,
+    input: &MediaSource,
+) -> Result<Run, Box<dyn Error>> {
+    let mut run = match args.value_of("output") {
+        Some(output) => Run::at(config, output)?,
+        None => Run::create(config, args.value_of("run-id"))?,
+    };
+
+    run.record("command", command)?;
+    run.record("input", input)?;
+
+    if let Some(clip) = input.clip() {
+        run.record("clip", clip)?;
+    }
+
+    Ok(run)
+}
+
+/// Builds the settings of the visual pipeline from the flags.
+fn visual_config(args: &ArgMatches, config: &Config) -> Result<VisualConfig, Box<dyn Error>> {
+    let heat_source: HeatSource = args.value_of("heat-source").unwrap().parse()?;
+    let element: StructuringElement = args.value_of("element").unwrap().parse()?;
+    let feather: u32 = args.value_of("feather").unwrap().parse()?;
+
+    let splitting = match args.value_of("splitting").unwrap() {
+        "erode" => SplittingStrategy::Erode(element),
+        "open" => SplittingStrategy::Open(element),
+        "watershed" => SplittingStrategy::Watershed,
+        _ => SplittingStrategy::Peel,
+    };
+
+    let crop = match args.value_of("crop").unwrap() {
+        "masked" => CropMode::Masked { feather },
+        _ => CropMode::Rectangle,
+    };
+
+    Ok(VisualConfig {
+        cell_size: config.cell_size,
+        heat_source,
+        edge_orientations: args.is_present("edge-orientations"),
+        splitting,
+        crop,
+    })
+}
+
+/// Builds the settings of the auditory pipeline from the flags.
+fn auditory_config(args: &ArgMatches) -> Result<AuditoryConfig, Box<dyn Error>> {
+    let window: Window = args.value_of("window").unwrap().parse()?;
+
+    let max_frequency = match args.value_of("max-frequency") {
+        Some(frequency) => Some(frequency.parse()?),
+        None => None,
+    };
+
+    let config = AuditoryConfig {
+        window,
+        frame_length: args.value_of("frame-length").unwrap().parse()?,
+        hop: args.value_of("hop").unwrap().parse()?,
+        mel_bands: args.value_of("mel-bands").unwrap().parse()?,
+        min_frequency: args.value_of("min-frequency").unwrap().parse()?,
+        max_frequency,
+        mfcc_coefficients: args.value_of("mfccs").unwrap().parse()?,
+        min_pitch: args.value_of("min-pitch").unwrap().parse()?,
+        max_pitch: args.value_of("max-pitch").unwrap().parse()?,
+        pitch_threshold: args.value_of("pitch-threshold").unwrap().parse()?,
+        onset_threshold: args.value_of("onset-threshold").unwrap().parse()?,
+        min_onset_gap: args.value_of("min-onset-gap").unwrap().parse()?,
+        min_event_duration: args.value_of("min-event-duration").unwrap().parse()?,
+        activity_threshold: args.value_of("activity-threshold").unwrap().parse()?,
+        activity_hangover: args.value_of("activity-hangover").unwrap().parse()?,
+        frame_overlap: args.value_of("overlap").unwrap().parse()?,
+    };
+
+    config.validate()?;
+
+    Ok(config)
+}
+
+/// Flags that override the configuration, see the `config` module. They can
+/// be given before or after the subcommand.
+fn config_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("data-root")
+            .long("data-root")
+            .takes_value(true)
+            .global(true)
+            .value_name("PATH")
+            .help("Directory with the clips [env: DATA_ROOT]"),
+        Arg::with_name("output-root")
+            .long("output-root")
+            .takes_value(true)
+            .global(true)
+            .value_name("PATH")
+            .help("Directory the results are written into [env: OUTPUT_ROOT]"),
+        Arg::with_name("cell-size")
+            .long("cell-size")
+            .takes_value(true)
+            .global(true)
+            .help("Size of the side of a heat map cell in pixels [env: CELL_SIZE]"),
+        Arg::with_name("fps")
+            .long("fps")
+            .takes_value(true)
+            .global(true)
+            .help("Frame rate the frames were extracted at [env: FPS]"),
+        Arg::with_name("sample-rate")
+            .long("sample-rate")
+            .takes_value(true)
+            .global(true)
+            .help("Sample rate the audio is resampled to [env: SAMPLE_RATE]"),
+        Arg::with_name("run-id")
+            .long("run-id")
+            .takes_value(true)
+            .global(true)
+            .help("Name of the run directory in the output root, defaults to the current time"),
+    ]
+}
+
+/// Flags with the parameters of the visual pipeline.
+fn visual_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("heat-source")
+            .long("heat-source")
+            .takes_value(true)
+            .possible_values(&["edges", "saliency", "combined"])
+            .default_value("edges")
+            .help("Map that feeds the cellular automaton"),
+        Arg::with_name("splitting")
+            .long("splitting")
+            .takes_value(true)
+            .possible_values(&["peel", "erode", "open", "watershed"])
+            .default_value("peel")
+            .help("How objects that are too large are split"),
+        Arg::with_name("element")
+            .long("element")
+            .takes_value(true)
+            .default_value("square:1")
+            .help("Structuring element of erode and open splitting, e.g. disk:2 or custom:0,0/1,0"),
+        Arg::with_name("edge-orientations")
+            .long("edge-orientations")
+            .help("Keeps orientation histogram of edges for each object"),
+        Arg::with_name("crop")
+            .long("crop")
+            .takes_value(true)
+            .possible_values(&["rectangle", "masked"])
+            .default_value("rectangle")
+            .help("How highlights are cut out of the frame"),
+        Arg::with_name("feather")
+            .long("feather")
+            .takes_value(true)
+            .default_value("0")
+            .help("Width of the fade out of masked highlights in pixels"),
+    ]
+}
+
+/// Optional directory of the run, defaults to a new directory in the output
+/// root.
+fn output_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("output")
+        .long("output")
+        .takes_value(true)
+        .value_name("PATH")
+        .help("Directory the results are saved to instead of a new run directory")
+}
+
+/// Flags with the parameters of the auditory pipeline.
+fn auditory_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("window")
+            .long("window")
+            .takes_value(true)
+            .possible_values(&["hann", "hamming"])
+            .default_value("hann")
+            .help("Window of the STFT frames"),
+        Arg::with_name("frame-length")
+            .long("frame-length")
+            .takes_value(true)
+            .default_value("2048")
+            .help("Number of samples in each STFT frame"),
+        Arg::with_name("hop")
+            .long("hop")
+            .takes_value(true)
+            .default_value("512")
+            .help("Number of samples between the starts of STFT frames"),
+        Arg::with_name("mel-bands")
+            .long("mel-bands")
+            .takes_value(true)
+            .default_value("40")
+            .help("Number of filters of the mel filterbank"),
+        Arg::with_name("min-frequency")
+            .long("min-frequency")
+            .takes_value(true)
+            .default_value("20")
+            .help("Lowest frequency of the mel filterbank in Hz"),
+        Arg::with_name("max-frequency")
+            .long("max-frequency")
+            .takes_value(true)
+            .help("Highest frequency of the mel filterbank in Hz, defaults to Nyquist"),
+        Arg::with_name("mfccs")
+            .long("mfccs")
+            .takes_value(true)
+            .default_value("13")
+            .help("Number of cepstral coefficients of each frame"),
+        Arg::with_name("min-pitch")
+            .long("min-pitch")
+            .takes_value(true)
+            .default_value("50")
+            .help("Lowest pitch in Hz the pitch tracker looks for"),
+        Arg::with_name("max-pitch")
+            .long("max-pitch")
+            .takes_value(true)
+            .default_value("2000")
+            .help("Highest pitch in Hz the pitch tracker looks for"),
+        Arg::with_name("pitch-threshold")
+            .long("pitch-threshold")
+            .takes_value(true)
+            .default_value("0.15")
+            .help("Highest difference of a frame and its period that is voiced, from 0 to 1"),
+        Arg::with_name("onset-threshold")
+            .long("onset-threshold")
+            .takes_value(true)
+            .default_value("0.1")
+            .help("How far above the local mean the flux of an onset is, from 0 to 1"),
+        Arg::with_name("min-onset-gap")
+            .long("min-onset-gap")
+            .takes_value(true)
+            .default_value("0.05")
+            .help("Shortest time in seconds between two onsets"),
+        Arg::with_name("min-event-duration")
+            .long("min-event-duration")
+            .takes_value(true)
+            .default_value("0.05")
+            .help("Shortest sound event in seconds, shorter ones are dropped as clicks"),
+        Arg::with_name("activity-threshold")
+            .long("activity-threshold")
+            .takes_value(true)
+            .default_value("12")
+            .help("How many decibels above the room noise a sound is"),
+        Arg::with_name("activity-hangover")
+            .long("activity-hangover")
+            .takes_value(true)
+            .default_value("0.2")
+            .help("Seconds for which a sound is held after it falls silent"),
+        Arg::with_name("overlap")
+            .long("overlap")
+            .takes_value(true)
+            .default_value("0")
+            .help("Share of the audio window of a video frame shared with each neighbour"),
+    ]
+}
+
+fn path_arg<'a>(name: &'a str, help: &'a str) -> Arg<'a, 'a> {
+    Arg::with_name(name)
+        .long(name)
+        .takes_value(true)
+        .required(true)
+        .value_name("PATH")
+        .help(help)
+}
@@ -0,0 +1,174 @@
+//! Settings shared by all subcommands.
+//!
+//! Each setting is taken from the first place it is found in:
+//!
+//! 1. command line flag, e.g. `--cell-size 20`,
+//! 2. environment variable, e.g. `CELL_SIZE=20`,
+//! 3. `.env` file in the working directory or any of its parents,
+//! 4. default value.
+//!
+//! The `.env` file never overrides variables that are already set in the
+//! environment.
+
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use dotenv;
+
+use senses::visual::CELL_SIZE;
+
+/// Directory with the clips, see the dataset layout.
+pub const DEFAULT_DATA_ROOT: &str = "data";
+
+/// Directory the results are written into.
+pub const DEFAULT_OUTPUT_ROOT: &str = "output";
+
+/// Frame rate the frames of the clips were extracted at.
+pub const DEFAULT_FPS: u32 = 4;
+
+/// Sample rate the audio of the clips is resampled to.
+pub const DEFAULT_SAMPLE_RATE: u32 = 44100;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    pub data_root: PathBuf,
+    pub output_root: PathBuf,
+
+    /// Size of the side of a heat map cell in pixels.
+    pub cell_size: u32,
+
+    /// Frame rate the frames of the clips were extracted at.
+    pub fps: u32,
+
+    /// Sample rate the audio of the clips is resampled to.
+    pub sample_rate: u32,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The `.env` file exists but cannot be read or parsed.
+    DotEnv(String),
+
+    /// Value of a setting is not valid.
+    Invalid {
+        key: String,
+        value: String,
+        reason: String,
+    },
+}
+
+impl Config {
+    /// Loads the `.env` file and reads the settings from the environment on
+    /// top of the defaults.
+    pub fn load() -> Result<Config, ConfigError> {
+        if let Err(error) = dotenv::dotenv() {
+            if !error.not_found() {
+                return Err(ConfigError::DotEnv(error.to_string()));
+            }
+        }
+
+        Config::default().merge(|key| env::var(key).ok())
+    }
+
+    /// Overrides the settings with values given by the lookup for each key,
+    /// e.g. `CELL_SIZE`. Values that are missing keep the current setting.
+    pub fn merge<F>(self, lookup: F) -> Result<Config, ConfigError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let config = Config {
+            data_root: lookup("DATA_ROOT").map_or(self.data_root, PathBuf::from),
+            output_root: lookup("OUTPUT_ROOT").map_or(self.output_root, PathBuf::from),
+            cell_size: parse(&lookup, "CELL_SIZE", self.cell_size)?,
+            fps: parse(&lookup, "FPS", self.fps)?,
+            sample_rate: parse(&lookup, "SAMPLE_RATE", self.sample_rate)?,
+        };
+
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        // Heat map cells overlap by half, therefore the size has to be even.
+        if self.cell_size == 0 || !self.cell_size.is_multiple_of(2) {
+            return Err(invalid(
+                "CELL_SIZE",
+                self.cell_size,
+                "must be a positive even number",
+            ));
+        }
+
+        if self.fps == 0 {
+            return Err(invalid("FPS", self.fps, "must be positive"));
+        }
+
+        if self.sample_rate == 0 {
+            return Err(invalid("SAMPLE_RATE", self.sample_rate, "must be positive"));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            data_root: PathBuf::from(DEFAULT_DATA_ROOT),
+            output_root: PathBuf::from(DEFAULT_OUTPUT_ROOT),
+            cell_size: CELL_SIZE,
+            fps: DEFAULT_FPS,
+            sample_rate: DEFAULT_SAMPLE_RATE,
+        }
+    }
+}
+
+impl fmt::Display for Config {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(formatter, "data_root={}", self.data_root.display())?;
+        writeln!(formatter, "output_root={}", self.output_root.display())?;
+        writeln!(formatter, "cell_size={}", self.cell_size)?;
+        writeln!(formatter, "fps={}", self.fps)?;
+        writeln!(formatter, "sample_rate={}", self.sample_rate)
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::DotEnv(error) => write!(formatter, "Cannot load .env: {}", error),
+            ConfigError::Invalid { key, value, reason } => {
+                write!(formatter, "Invalid {}={}: {}", key, value, reason)
+            }
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+/// Parses the value of given key if there is any, otherwise keeps the current
+/// setting.
+fn parse<F, T>(lookup: &F, key: &str, current: T) -> Result<T, ConfigError>
+where
+    F: Fn(&str) -> Option<String>,
+    T: FromStr,
+{
+    match lookup(key) {
+        None => Ok(current),
+        Some(value) => value
+            .trim()
+            .parse()
+            .map_err(|_| invalid(key, value, "must be a whole number")),
+    }
+}
+
+fn invalid<V: ToString>(key: &str, value: V, reason: &str) -> ConfigError {
+    ConfigError::Invalid {
+        key: key.to_string(),
+        value: value.to_string(),
+        reason: reason.to_string(),
+    }
+}
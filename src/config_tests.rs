@@ -0,0 +1,119 @@
+//! Tests of the precedence of the sources of the settings and of their
+//! validation.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use config::{Config, ConfigError};
+use dotenv;
+
+/// Keys of all settings.
+const KEYS: [&str; 5] = [
+    "DATA_ROOT",
+    "OUTPUT_ROOT",
+    "CELL_SIZE",
+    "FPS",
+    "SAMPLE_RATE",
+];
+
+/// Lookup of the given keys and values, e.g. the flags of the command line.
+fn lookup(values: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let values: Vec<(String, String)> = values
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+
+    move |key| {
+        values
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.clone())
+    }
+}
+
+/// Key of the setting that was rejected.
+fn invalid_key(result: Result<Config, ConfigError>) -> String {
+    match result {
+        Err(ConfigError::Invalid { key, .. }) => key,
+        other => panic!("expected invalid setting, got {:?}", other),
+    }
+}
+
+#[test]
+fn missing_values_keep_the_defaults() {
+    let config = Config::default().merge(lookup(&[])).unwrap();
+
+    assert_eq!(config, Config::default());
+}
+
+#[test]
+fn flag_overrides_environment_which_overrides_dot_env_and_defaults() {
+    // This is the only test that touches the environment of the process.
+    for key in KEYS.iter() {
+        env::remove_var(key);
+    }
+
+    let dot_env = env::temp_dir().join(format!("config-{}.env", process::id()));
+    fs::write(&dot_env, "CELL_SIZE=20\nFPS=8\nSAMPLE_RATE=22050\n").unwrap();
+
+    // The .env file does not override variables that are already set.
+    env::set_var("FPS", "12");
+    env::set_var("SAMPLE_RATE", "16000");
+    dotenv::from_path(&dot_env).unwrap();
+    fs::remove_file(&dot_env).unwrap();
+
+    // Loading merges the environment, the command line then merges its flags.
+    let config = Config::default()
+        .merge(|key| env::var(key).ok())
+        .unwrap()
+        .merge(lookup(&[("SAMPLE_RATE", "8000"), ("DATA_ROOT", "clips")]))
+        .unwrap();
+
+    for key in KEYS.iter() {
+        env::remove_var(key);
+    }
+
+    assert_eq!(config.data_root, PathBuf::from("clips"));
+    assert_eq!(config.output_root, Config::default().output_root);
+    assert_eq!(config.cell_size, 20);
+    assert_eq!(config.fps, 12);
+    assert_eq!(config.sample_rate, 8000);
+}
+
+#[test]
+fn values_are_trimmed() {
+    let config = Config::default()
+        .merge(lookup(&[("CELL_SIZE", " 16 ")]))
+        .unwrap();
+
+    assert_eq!(config.cell_size, 16);
+}
+
+#[test]
+fn cell_size_has_to_be_positive_and_even() {
+    for size in ["15", "0"].iter() {
+        let result = Config::default().merge(lookup(&[("CELL_SIZE", size)]));
+
+        assert_eq!(invalid_key(result), "CELL_SIZE", "cell size {}", size);
+    }
+}
+
+#[test]
+fn frame_and_sample_rates_have_to_be_positive() {
+    let result = Config::default().merge(lookup(&[("FPS", "0")]));
+    assert_eq!(invalid_key(result), "FPS");
+
+    let result = Config::default().merge(lookup(&[("SAMPLE_RATE", "0")]));
+    assert_eq!(invalid_key(result), "SAMPLE_RATE");
+}
+
+#[test]
+fn values_that_are_not_numbers_are_invalid() {
+    let result = Config::default().merge(lookup(&[("FPS", "four")]));
+    assert_eq!(invalid_key(result), "FPS");
+
+    let result = Config::default().merge(lookup(&[("SAMPLE_RATE", "-1")]));
+    assert_eq!(invalid_key(result), "SAMPLE_RATE");
+}
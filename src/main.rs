@@ -1,8 +1,26 @@
+extern crate chrono;
+extern crate clap;
+extern crate dotenv;
+#[cfg(test)]
+extern crate proptest;
+
+mod cli;
+mod config;
+#[cfg(test)]
+mod config_tests;
+mod run;
+#[cfg(test)]
+mod run_tests;
 mod senses;
 mod som;
 
-use som::SelfOrganizingMap;
+use std::process;
 
 fn main() {
-    let som = SelfOrganizingMap::new(vec![]);
+    let matches = cli::app().get_matches();
+
+    if let Err(error) = cli::run(&matches) {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
 }
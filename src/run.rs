@@ -0,0 +1,174 @@
+//! Each run of a subcommand writes its results into its own directory, so that
+//! runs never overwrite one another. The directory also holds `run.txt` with
+//! the settings the run was made with.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use chrono::Local;
+
+use config::Config;
+
+/// Name of the file with the metadata of the run.
+pub const METADATA_FILE: &str = "run.txt";
+
+/// Format of the timestamp that names the runs without an explicit id.
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+
+pub struct Run {
+    id: String,
+    directory: PathBuf,
+    config: Config,
+    started: String,
+
+    /// Subcommand and inputs of the run as key and value pairs.
+    entries: Vec<(String, String)>,
+}
+
+impl Run {
+    /// Creates the directory of a new run in the output root. The run is named
+    /// by the id if given, otherwise by the current time. Existing runs are
+    /// never reused: an explicit id that is taken is an error and a taken
+    /// timestamp gets a numeric suffix. The id has to be a plain directory
+    /// name, so that the run stays inside of the output root.
+    pub fn create(config: &Config, id: Option<&str>) -> io::Result<Run> {
+        let now = Local::now();
+        let timestamp = now.format(TIMESTAMP_FORMAT).to_string();
+
+        fs::create_dir_all(&config.output_root)?;
+
+        // Creating the directory fails if it exists, so that two runs started
+        // at once cannot both take it.
+        let (id, directory) = match id {
+            Some(id) => {
+                validate_id(id)?;
+
+                let directory = config.output_root.join(id);
+                fs::create_dir(&directory).map_err(|error| match error.kind() {
+                    io::ErrorKind::AlreadyExists => io::Error::new(
+                        io::ErrorKind::AlreadyExists,
+                        format!(
+                            "Run {} already exists in {}",
+                            id,
+                            config.output_root.display()
+                        ),
+                    ),
+                    _ => error,
+                })?;
+
+                (id.to_string(), directory)
+            }
+            None => {
+                let mut attempt = 1;
+
+                loop {
+                    let id = match attempt {
+                        1 => timestamp.clone(),
+                        _ => format!("{}_{}", timestamp, attempt),
+                    };
+                    let directory = config.output_root.join(&id);
+
+                    match fs::create_dir(&directory) {
+                        Ok(()) => break (id, directory),
+                        Err(ref error) if error.kind() == io::ErrorKind::AlreadyExists => {
+                            attempt += 1
+                        }
+                        Err(error) => return Err(error),
+                    }
+                }
+            }
+        };
+
+        Run::in_directory(config, id, directory, now.to_rfc3339())
+    }
+
+    /// Uses given directory for the run, e.g. when the output is given on the
+    /// command line. The directory is created if it does not exist.
+    pub fn at<P: Into<PathBuf>>(config: &Config, directory: P) -> io::Result<Run> {
+        let directory: PathBuf = directory.into();
+        let id = directory
+            .file_name()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+
+        fs::create_dir_all(&directory)?;
+
+        Run::in_directory(config, id, directory, Local::now().to_rfc3339())
+    }
+
+    fn in_directory(
+        config: &Config,
+        id: String,
+        directory: PathBuf,
+        started: String,
+    ) -> io::Result<Run> {
+        let run = Run {
+            id,
+            directory,
+            config: config.clone(),
+            started,
+            entries: Vec::new(),
+        };
+
+        run.save()?;
+
+        Ok(run)
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Path of a file with given name in the directory of the run.
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.directory.join(name)
+    }
+
+    /// Adds an entry to the metadata, e.g. the input clip, and saves it.
+    pub fn record<V: ToString>(&mut self, key: &str, value: V) -> io::Result<()> {
+        self.entries.push((key.to_string(), value.to_string()));
+
+        self.save()
+    }
+
+    fn save(&self) -> io::Result<()> {
+        fs::write(self.path(METADATA_FILE), self.to_string())
+    }
+}
+
+/// Checks that the id names a single directory, i.e. it has no separators and
+/// is not `.` or `..`.
+fn validate_id(id: &str) -> io::Result<()> {
+    let mut components = Path::new(id).components();
+    let is_plain = match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) => !id.contains(['/', '\\']),
+        _ => false,
+    };
+
+    if is_plain {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Run id {} has to be a plain directory name", id),
+        ))
+    }
+}
+
+impl fmt::Display for Run {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(formatter, "id={}", self.id)?;
+        writeln!(formatter, "started={}", self.started)?;
+
+        for (key, value) in &self.entries {
+            writeln!(formatter, "{}={}", key, value)?;
+        }
+
+        write!(formatter, "{}", self.config)
+    }
+}
@@ -0,0 +1,66 @@
+//! Tests of creating the directories of runs in the output root.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process;
+
+use config::Config;
+use run::Run;
+
+/// Configuration with an empty output root of its own.
+fn config(name: &str) -> Config {
+    let output_root: PathBuf = env::temp_dir().join(format!("runs-{}-{}", name, process::id()));
+    let _ = fs::remove_dir_all(&output_root);
+
+    Config {
+        output_root,
+        ..Config::default()
+    }
+}
+
+#[test]
+fn run_with_taken_id_is_an_error() {
+    let config = config("taken");
+
+    let run = Run::create(&config, Some("first")).unwrap();
+    assert_eq!(run.directory(), config.output_root.join("first"));
+    assert!(run.path("run.txt").exists());
+
+    let error = Run::create(&config, Some("first")).err().unwrap();
+    assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+
+    fs::remove_dir_all(&config.output_root).unwrap();
+}
+
+#[test]
+fn runs_started_at_once_get_suffixes() {
+    let config = config("suffixes");
+
+    let ids: Vec<String> = (0..3)
+        .map(|_| Run::create(&config, None).unwrap().id().to_string())
+        .collect();
+
+    // Runs started within the same second share the timestamp.
+    let mut unique = ids.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), 3, "{:?}", ids);
+
+    fs::remove_dir_all(&config.output_root).unwrap();
+}
+
+#[test]
+fn ids_that_leave_the_output_root_are_rejected() {
+    let config = config("escape");
+
+    for id in &["..", ".", "", "a/b", "../escaped", "a\\b", "/tmp"] {
+        let error = Run::create(&config, Some(id)).err().unwrap();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput, "{:?}", id);
+    }
+    assert!(!config.output_root.join("..").join("escaped").exists());
+
+    fs::remove_dir_all(&config.output_root).unwrap();
+}
@@ -0,0 +1,192 @@
+use std::fs;
+use std::path::Path;
+
+use senses::error::SensesError;
+use senses::auditory::config::AuditoryConfig;
+use senses::auditory::signal::{zero_crossing_rate, Signal};
+
+/// Share of the quietest frames of the signal that are taken for room noise.
+const NOISE_SHARE: f32 = 0.1_f32;
+
+/// How many standard deviations above the mean zero-crossing rate of the
+/// noise a quiet frame has to be to count as a sound, e.g. a fricative.
+const ZCR_DEVIATIONS: f32 = 2_f32;
+
+/// Energy in decibels of a frame of digital silence, so that its logarithm is
+/// finite.
+const SILENT_FRAME: f32 = -120_f32;
+
+/// Stretch of the signal that is either all silence or all activity.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActivityRegion {
+
+  /// Time the region starts at in seconds.
+  pub start: f64,
+
+  /// Time the region ends at in seconds.
+  pub end: f64,
+
+  /// Whether there is a sound in the region or just room noise.
+  pub active: bool,
+
+}
+
+impl ActivityRegion {
+
+  pub fn duration(&self) -> f64 {
+    self.end - self.start
+  }
+}
+
+/// Signal split into alternating silent and active regions which together
+/// cover all of it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Activity {
+  pub regions: Vec<ActivityRegion>,
+}
+
+impl Activity {
+
+  /// Regions with a sound in them, the parts of the signal worth analysing.
+  pub fn active_regions(&self) -> Vec<&ActivityRegion> {
+    self.regions.iter().filter(|region| region.active).collect()
+  }
+
+  /// Whether there is a sound at given time in seconds.
+  pub fn is_active(&self, time: f64) -> bool {
+    self.regions.iter().any(|region| region.active && region.start <= time && time < region.end)
+  }
+
+  /// Share of the signal that is active, from 0 to 1.
+  pub fn active_share(&self) -> f64 {
+    let total: f64 = self.regions.iter().map(|region| region.duration()).sum();
+    let active: f64 = self.active_regions().iter().map(|region| region.duration()).sum();
+
+    if total > 0_f64 { active / total } else { 0_f64 }
+  }
+
+  /// Writes a CSV file with one region per line.
+  pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SensesError> {
+    let mut csv: String = String::from("start,end,active\n");
+
+    for region in self.regions.iter() {
+      csv.push_str(&format!("{},{},{}\n", region.start, region.end, region.active));
+    }
+
+    fs::write(path, csv)?;
+
+    Ok(())
+  }
+
+}
+
+/// Marks the regions of the signal where there is a sound. The signal is cut
+/// into frames of the length and hop of the STFT and each frame is compared
+/// to the room noise, which is estimated from the quietest frames. A frame is
+/// active if its energy is above the noise by the activity threshold, or if it
+/// is above the noise by half of the threshold and crosses zero more often
+/// than the noise does, which catches quiet but noisy sounds such as
+/// fricatives.
+///
+/// Activity is held for the hangover after the last active frame, so that
+/// short pauses within a sound do not split it. A signal without any quiet
+/// stretch has nothing to estimate the noise from, therefore the noise is
+/// never taken to be closer than twice the threshold to the loudest frame.
+pub fn detect_activity(signal: &Signal, config: &AuditoryConfig) -> Activity {
+  let (frame_length, hop) = (config.frame_length, config.hop);
+  let samples = &signal.samples;
+
+  if samples.is_empty() {
+    return Activity { regions: Vec::new() };
+  }
+
+  let count = if samples.len() <= frame_length {
+    1
+  } else {
+    1 + (samples.len() - frame_length).div_ceil(hop)
+  };
+
+  let (energies, rates): (Vec<f32>, Vec<f32>) = (0..count).map(|index| {
+    let start = index * hop;
+    let frame = &samples[start..(start + frame_length).min(samples.len())];
+
+    (energy(frame), zero_crossing_rate(frame))
+  }).unzip();
+
+  // Noise is described by the quietest frames.
+  let mut order: Vec<usize> = (0..count).collect();
+  order.sort_by(|a, b| energies[*a].total_cmp(&energies[*b]));
+  let quietest = &order[..((count as f32 * NOISE_SHARE).ceil() as usize).max(1)];
+
+  let loudest = energies.iter().fold(SILENT_FRAME, |max, energy| max.max(*energy));
+  let noise_energy = mean(quietest.iter().map(|index| energies[*index]))
+    .min(loudest - 2_f32 * config.activity_threshold);
+
+  let noise_rate = mean(quietest.iter().map(|index| rates[*index]));
+  let rate_deviation = mean(quietest.iter().map(|index| (rates[*index] - noise_rate).powi(2))).sqrt();
+  let rate_threshold = noise_rate + ZCR_DEVIATIONS * rate_deviation;
+
+  let hangover = (config.activity_hangover * signal.sample_rate as f64 / hop as f64).round() as usize;
+  let mut held = 0;
+
+  let active: Vec<bool> = energies.iter().zip(rates.iter()).map(|(energy, rate)| {
+    let above = energy - noise_energy;
+    let is_loud = above >= config.activity_threshold;
+    let is_noisy = above >= config.activity_threshold / 2_f32 && *rate > rate_threshold;
+
+    if is_loud || is_noisy {
+      held = hangover;
+      true
+    } else if held > 0 {
+      held -= 1;
+      true
+    } else {
+      false
+    }
+  }).collect();
+
+  // Each frame stands for the hop around its centre, the first and the last
+  // region are stretched to the ends of the signal.
+  let boundary = |index: usize| {
+    if index == 0 {
+      0_f64
+    } else if index == count {
+      signal.duration()
+    } else {
+      ((index * hop + frame_length / 2) as f64 - hop as f64 / 2_f64).max(0_f64) / signal.sample_rate as f64
+    }
+  };
+
+  let mut regions: Vec<ActivityRegion> = Vec::new();
+  let mut first = 0;
+
+  for index in 1..(count + 1) {
+    if index == count || active[index] != active[first] {
+      regions.push(ActivityRegion {
+        start: boundary(first),
+        end: boundary(index).min(signal.duration()),
+        active: active[first],
+      });
+      first = index;
+    }
+  }
+
+  Activity { regions }
+}
+
+/// Mean power of the samples in decibels relative to full scale.
+fn energy(frame: &[f32]) -> f32 {
+  let power = frame.iter().map(|sample| sample * sample).sum::<f32>() / frame.len() as f32;
+
+  if power > 0_f32 {
+    (10_f32 * power.log10()).max(SILENT_FRAME)
+  } else {
+    SILENT_FRAME
+  }
+}
+
+fn mean<I: Iterator<Item = f32>>(values: I) -> f32 {
+  let (total, count) = values.fold((0_f32, 0), |(total, count), value| (total + value, count + 1));
+
+  if count > 0 { total / count as f32 } else { 0_f32 }
+}
@@ -0,0 +1,109 @@
+//! Tests of the activity detector on tones separated by silence.
+
+use std::env;
+use std::f32::consts::PI;
+use std::fs;
+use std::process;
+
+use senses::auditory::activity::{detect_activity, Activity};
+use senses::auditory::config::AuditoryConfig;
+use senses::auditory::hound::{SampleFormat, WavSpec, WavWriter};
+use senses::auditory::load_wav::load_wav;
+use senses::auditory::signal::Signal;
+use senses::media_source::{MediaKind, MediaSource};
+
+const SAMPLE_RATE: u32 = 16000;
+
+/// Signal of given parts, each is a duration in seconds and whether it is a
+/// tone. Between the tones there is quiet noise of the room.
+fn signal(parts: &[(f32, bool)]) -> Signal {
+  let mut samples: Vec<f32> = Vec::new();
+  let mut state = 1_u32;
+
+  for (duration, is_tone) in parts {
+    for _ in 0..(duration * SAMPLE_RATE as f32) as usize {
+      state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+      let noise = 0.001_f32 * ((state >> 16) as f32 / 32768_f32 - 1_f32);
+      let tone = if *is_tone {
+        0.5_f32 * (2_f32 * PI * 440_f32 * samples.len() as f32 / SAMPLE_RATE as f32).sin()
+      } else {
+        0_f32
+      };
+
+      samples.push(tone + noise);
+    }
+  }
+
+  Signal::new(samples, SAMPLE_RATE)
+}
+
+fn config(hangover: f64) -> AuditoryConfig {
+  AuditoryConfig { frame_length: 512, hop: 256, activity_hangover: hangover, ..AuditoryConfig::default() }
+}
+
+fn flags(activity: &Activity) -> Vec<bool> {
+  activity.regions.iter().map(|region| region.active).collect()
+}
+
+#[test]
+fn tones_are_active_and_the_silence_between_them_is_not() {
+  let config = config(0.05_f64);
+  let activity = detect_activity(&signal(&[(1_f32, true), (1_f32, false), (1_f32, true)]), &config);
+
+  assert_eq!(flags(&activity), vec!(true, false, true), "{:?}", activity);
+
+  // Frames that reach into a tone are active, the first tone is then held for
+  // the hangover.
+  let frame = config.frame_length as f64 / SAMPLE_RATE as f64;
+  let (first, silence, second) = (&activity.regions[0], &activity.regions[1], &activity.regions[2]);
+
+  assert_eq!(first.start, 0_f64);
+  assert!(first.end >= 1_f64 && first.end <= 1_f64 + config.activity_hangover + frame, "{:?}", first);
+  assert_eq!(silence.start, first.end);
+  assert!(second.start >= 2_f64 - frame && second.start <= 2_f64, "{:?}", second);
+  assert!((second.end - 3_f64).abs() < 1e-9_f64);
+
+  assert!(!activity.is_active(1.5_f64));
+  assert!(activity.is_active(0.5_f64) && activity.is_active(2.5_f64));
+}
+
+#[test]
+fn hangover_bridges_a_short_pause() {
+  let parts = [(0.5_f32, true), (0.1_f32, false), (0.5_f32, true), (0.5_f32, false)];
+
+  assert_eq!(flags(&detect_activity(&signal(&parts), &config(0.2_f64))), vec!(true, false));
+  assert_eq!(flags(&detect_activity(&signal(&parts), &config(0_f64))), vec!(true, false, true, false));
+}
+
+#[test]
+fn signal_without_pauses_is_all_active() {
+  let signal = signal(&[(1_f32, true)]);
+  let activity = detect_activity(&signal, &config(0_f64));
+
+  assert_eq!(flags(&activity), vec!(true));
+  assert!((activity.active_share() - 1_f64).abs() < 1e-9_f64);
+}
+
+#[test]
+fn samples_that_are_not_finite_are_loaded_as_silence() {
+  let path = env::temp_dir().join(format!("activity-{}.wav", process::id()));
+  let spec = WavSpec { channels: 1, sample_rate: SAMPLE_RATE, bits_per_sample: 32, sample_format: SampleFormat::Float };
+  let mut writer = WavWriter::create(&path, spec).unwrap();
+
+  for n in 0..SAMPLE_RATE {
+    let sample = match n % 100 {
+      0 => f32::NAN,
+      1 => f32::INFINITY,
+      _ => 0.5_f32 * (n as f32 * 0.1_f32).sin(),
+    };
+    writer.write_sample(sample).unwrap();
+  }
+  writer.finalize().unwrap();
+
+  let signal = load_wav(&MediaSource::new(&path, MediaKind::Wav)).unwrap();
+  fs::remove_file(&path).unwrap();
+
+  assert!(signal.samples.iter().all(|sample| sample.is_finite()));
+  assert_eq!((signal.samples[0], signal.samples[101]), (0_f32, 0_f32));
+  assert_eq!(flags(&detect_activity(&signal, &config(0_f64))), vec!(true));
+}
@@ -0,0 +1,48 @@
+use std::fs;
+use std::path::Path;
+
+use senses::error::SensesError;
+
+/// Sound event found in the signal, the auditory counterpart of a
+/// `VisualObject`. Where a visual object is a group of cells of a frame, an
+/// auditory object is a stretch of frames of a spectrogram.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuditoryObject {
+
+  /// Time the sound starts at in seconds.
+  pub start: f64,
+
+  /// Time the sound ends at in seconds.
+  pub end: f64,
+
+  /// Index of the first spectrogram frame of the sound.
+  pub first_frame: usize,
+
+  /// Index of the frame after the last one of the sound.
+  pub end_frame: usize,
+
+  /// Spectral flux at the onset of the sound relative to the strongest onset
+  /// in the signal, from 0 to 1.
+  pub strength: f32,
+
+}
+
+impl AuditoryObject {
+
+  pub fn duration(&self) -> f64 {
+    self.end - self.start
+  }
+}
+
+/// Writes a CSV with the start, end and onset strength of each sound event.
+pub fn save_events<P: AsRef<Path>>(events: &[AuditoryObject], path: P) -> Result<(), SensesError> {
+  let mut csv: String = String::from("start,end,strength\n");
+
+  for event in events {
+    csv.push_str(&format!("{},{},{}\n", event.start, event.end, event.strength));
+  }
+
+  fs::write(path, csv)?;
+
+  Ok(())
+}
@@ -0,0 +1,193 @@
+use std::str::FromStr;
+
+/// Shape of the window each frame of the STFT is multiplied by before the
+/// transform, which reduces the leakage of energy into neighbouring bins.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Window {
+
+  /// Raised cosine that falls to zero at both ends. Leaks little energy far
+  /// from the peak.
+  Hann,
+
+  /// Raised cosine that stays at 0.08 at the ends. Has a lower closest side
+  /// lobe than Hann but leaks more energy far from the peak.
+  Hamming,
+
+}
+
+impl Window {
+
+  /// Weights of the window of given length.
+  pub fn weights(&self, length: usize) -> Vec<f32> {
+    let (a0, a1) = match self {
+      Window::Hann => (0.5_f32, 0.5_f32),
+      Window::Hamming => (0.54_f32, 0.46_f32),
+    };
+
+    // Periodic window, so that overlapping frames add up evenly.
+    (0..length).map(|n| {
+      a0 - a1 * (2_f32 * ::std::f32::consts::PI * n as f32 / length as f32).cos()
+    }).collect()
+  }
+
+}
+
+impl FromStr for Window {
+
+  type Err = String;
+
+  fn from_str(name: &str) -> Result<Window, String> {
+    match name {
+      "hann" => Ok(Window::Hann),
+      "hamming" => Ok(Window::Hamming),
+      _ => Err(format!("Unknown window {}", name)),
+    }
+  }
+
+}
+
+/// Settings of the auditory pipeline.
+#[derive(Clone, Debug)]
+pub struct AuditoryConfig {
+
+  /// Window applied to each frame of the STFT.
+  pub window: Window,
+
+  /// Number of samples in each frame of the STFT. Frequency resolution of the
+  /// spectrogram is the sample rate divided by it.
+  pub frame_length: usize,
+
+  /// Number of samples between the starts of two consecutive frames.
+  pub hop: usize,
+
+  /// Number of triangular filters of the mel filterbank.
+  pub mel_bands: usize,
+
+  /// Lowest frequency in Hz that the mel filterbank covers.
+  pub min_frequency: f32,
+
+  /// Highest frequency in Hz that the mel filterbank covers. None means the
+  /// Nyquist frequency of the signal.
+  pub max_frequency: Option<f32>,
+
+  /// Number of cepstral coefficients kept of each frame, including the 0th
+  /// one which follows the loudness of the frame.
+  pub mfcc_coefficients: usize,
+
+  /// Lowest pitch in Hz the pitch tracker looks for. Its period has to fit
+  /// into half of the frame.
+  pub min_pitch: f32,
+
+  /// Highest pitch in Hz the pitch tracker looks for.
+  pub max_pitch: f32,
+
+  /// Frames whose normalized difference at the found period is below this
+  /// are voiced. Lower values accept only cleaner tones.
+  pub pitch_threshold: f32,
+
+  /// How far above the local mean the spectral flux of an onset has to be,
+  /// relative to the highest flux in the signal.
+  pub onset_threshold: f32,
+
+  /// Shortest time in seconds between two onsets.
+  pub min_onset_gap: f64,
+
+  /// Shortest sound event in seconds, shorter ones are clicks.
+  pub min_event_duration: f64,
+
+  /// How many decibels above the room noise a frame has to be to be active.
+  pub activity_threshold: f32,
+
+  /// Time in seconds for which the activity is held after the last active
+  /// frame.
+  pub activity_hangover: f64,
+
+  /// Share of the audio window of a video frame that is shared with the
+  /// window of each neighbouring frame, from 0 up to but excluding 1.
+  pub frame_overlap: f64,
+
+}
+
+impl AuditoryConfig {
+
+  /// Checks that the frames are long enough to be transformed, that they move
+  /// forward, that the mel bands and MFCCs fit together, that the pitch range
+  /// is not empty, that the onset, event and activity settings are not
+  /// negative and that the windows of video frames do not overlap completely.
+  pub fn validate(&self) -> Result<(), String> {
+    if self.frame_length < 2 {
+      return Err(format!("Frame length of {} samples is too short", self.frame_length));
+    }
+
+    if self.hop == 0 {
+      return Err("Hop has to be at least one sample".to_string());
+    }
+
+    if self.mel_bands == 0 {
+      return Err("There has to be at least one mel band".to_string());
+    }
+
+    if self.max_frequency.is_some_and(|max| max <= self.min_frequency) {
+      return Err("Highest frequency of the mel bands has to be above the lowest".to_string());
+    }
+
+    if self.mfcc_coefficients == 0 || self.mfcc_coefficients > self.mel_bands {
+      return Err(format!(
+        "Number of MFCCs has to be between 1 and the number of mel bands {}",
+        self.mel_bands,
+      ));
+    }
+
+    if self.min_pitch <= 0_f32 || self.max_pitch <= self.min_pitch {
+      return Err(format!("Pitch range {} to {} Hz is empty", self.min_pitch, self.max_pitch));
+    }
+
+    if self.pitch_threshold <= 0_f32 || self.pitch_threshold > 1_f32 {
+      return Err(format!("Pitch threshold {} has to be above 0 and up to 1", self.pitch_threshold));
+    }
+
+    if self.onset_threshold < 0_f32 || self.min_onset_gap < 0_f64 {
+      return Err("Onset threshold and gap cannot be negative".to_string());
+    }
+
+    if self.min_event_duration < 0_f64 {
+      return Err("Event duration cannot be negative".to_string());
+    }
+
+    if self.activity_threshold < 0_f32 || self.activity_hangover < 0_f64 {
+      return Err("Activity threshold and hangover cannot be negative".to_string());
+    }
+
+    if self.frame_overlap < 0_f64 || self.frame_overlap >= 1_f64 {
+      return Err(format!("Frame overlap {} has to be from 0 up to 1", self.frame_overlap));
+    }
+
+    Ok(())
+  }
+
+}
+
+impl Default for AuditoryConfig {
+
+  fn default() -> AuditoryConfig {
+    AuditoryConfig {
+      window: Window::Hann,
+      frame_length: 2048,
+      hop: 512,
+      mel_bands: 40,
+      min_frequency: 20_f32,
+      max_frequency: None,
+      mfcc_coefficients: 13,
+      min_pitch: 50_f32,
+      max_pitch: 2000_f32,
+      pitch_threshold: 0.15_f32,
+      onset_threshold: 0.1_f32,
+      min_onset_gap: 0.05_f64,
+      min_event_duration: 0.05_f64,
+      activity_threshold: 12_f32,
+      activity_hangover: 0.2_f64,
+      frame_overlap: 0_f64,
+    }
+  }
+
+}
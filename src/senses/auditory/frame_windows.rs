@@ -0,0 +1,157 @@
+use std::fs;
+use std::path::Path;
+
+use senses::error::SensesError;
+use senses::auditory::config::AuditoryConfig;
+use senses::auditory::mfcc::mfcc;
+use senses::auditory::pitch::track_pitch;
+use senses::auditory::signal::Signal;
+use senses::auditory::stft::stft;
+
+/// Part of the signal that belongs to a video frame, so that the auditory and
+/// the visual percepts of a moment share the timeline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AudioWindow {
+
+  /// Index of the video frame, the first frame is 0.
+  pub frame: usize,
+
+  /// Time the window starts at in seconds.
+  pub start: f64,
+
+  /// Time the window ends at in seconds.
+  pub end: f64,
+
+  /// Samples of the window. Windows that reach past the ends of the signal
+  /// are cut short.
+  pub signal: Signal,
+
+}
+
+impl AudioWindow {
+
+  /// Summarises the sound of the window: its loudness, its mean MFCCs and its
+  /// pitch.
+  pub fn features(&self, config: &AuditoryConfig) -> WindowFeatures {
+    let samples = &self.signal.samples;
+    let rms = if samples.is_empty() {
+      0_f32
+    } else {
+      (samples.iter().map(|sample| sample * sample).sum::<f32>() / samples.len() as f32).sqrt()
+    };
+
+    let coefficients = mfcc(&stft(&self.signal, config), config).coefficients;
+    let mfcc: Vec<f32> = (0..config.mfcc_coefficients).map(|index| {
+      coefficients.iter().map(|frame| frame[index]).sum::<f32>() / coefficients.len().max(1) as f32
+    }).collect();
+
+    // Pitch of the window is the median pitch of its voiced frames.
+    let track = track_pitch(&self.signal, config);
+    let mut voiced: Vec<f32> = track.frames.iter()
+      .filter(|frame| frame.voiced)
+      .map(|frame| frame.frequency)
+      .collect();
+    voiced.sort_by(|a, b| a.total_cmp(b));
+
+    WindowFeatures {
+      frame: self.frame,
+      start: self.start,
+      end: self.end,
+      rms,
+      pitch: voiced.get(voiced.len() / 2).cloned().unwrap_or(0_f32),
+      voiced: voiced.len() as f32 / track.frames.len().max(1) as f32,
+      mfcc,
+    }
+  }
+
+}
+
+/// Features of the audio window of a video frame.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowFeatures {
+
+  /// Index of the video frame.
+  pub frame: usize,
+
+  pub start: f64,
+
+  pub end: f64,
+
+  /// Root mean square of the samples.
+  pub rms: f32,
+
+  /// Median pitch of the voiced frames in Hz, 0 if none is voiced.
+  pub pitch: f32,
+
+  /// Share of the pitch frames that are voiced, from 0 to 1.
+  pub voiced: f32,
+
+  /// MFCCs averaged over the STFT frames of the window.
+  pub mfcc: Vec<f32>,
+
+}
+
+/// Cuts out the audio that belongs to given video frame. Frame n of a video at
+/// given frame rate stands for the time from n / fps to (n + 1) / fps. The
+/// window is centred on that time and stretched so that given share of it,
+/// from 0 up to but excluding 1, is shared with each of the neighbouring
+/// windows. Without overlap, the window is exactly the time of the frame.
+pub fn frame_window(signal: &Signal, fps: u32, frame: usize, overlap: f64) -> AudioWindow {
+  let period = 1_f64 / fps as f64;
+  let length = period / (1_f64 - overlap);
+  let centre = (frame as f64 + 0.5_f64) * period;
+
+  let start = (centre - length / 2_f64).max(0_f64).min(signal.duration());
+  let end = (centre + length / 2_f64).max(0_f64).min(signal.duration());
+  let samples = signal.samples[signal.sample_at(start)..signal.sample_at(end)].to_vec();
+
+  AudioWindow {
+    frame,
+    start,
+    end,
+    signal: Signal::new(samples, signal.sample_rate),
+  }
+}
+
+/// Windows of all video frames that the signal lasts for, in frame order.
+pub fn frame_windows(signal: &Signal, fps: u32, overlap: f64) -> Vec<AudioWindow> {
+  let frames = (signal.duration() * fps as f64).ceil() as usize;
+
+  (0..frames).map(|frame| frame_window(signal, fps, frame, overlap)).collect()
+}
+
+/// Writes a CSV with the features of one frame per line, the MFCCs are in the
+/// last columns.
+pub fn save_window_features<P: AsRef<Path>>(
+  features: &[WindowFeatures],
+  path: P,
+) -> Result<(), SensesError> {
+  let mut csv: String = String::from("frame,start,end,rms,pitch,voiced");
+
+  let coefficients = features.first().map_or(0, |features| features.mfcc.len());
+  for index in 0..coefficients {
+    csv.push_str(&format!(",mfcc_{}", index));
+  }
+  csv.push('\n');
+
+  for features in features {
+    csv.push_str(&format!(
+      "{},{},{},{},{},{}",
+      features.frame,
+      features.start,
+      features.end,
+      features.rms,
+      features.pitch,
+      features.voiced,
+    ));
+
+    for coefficient in features.mfcc.iter() {
+      csv.push_str(&format!(",{}", coefficient));
+    }
+    csv.push('\n');
+  }
+
+  fs::write(path, csv)?;
+
+  Ok(())
+}
@@ -0,0 +1,68 @@
+//! Tests of cutting the signal into the windows of video frames. The samples
+//! of the signal are their own indices, so that the samples of a window tell
+//! where it was cut from.
+
+use senses::auditory::frame_windows::{frame_window, frame_windows, AudioWindow};
+use senses::auditory::signal::Signal;
+
+const SAMPLE_RATE: u32 = 1000;
+
+const FPS: u32 = 10;
+
+/// Signal of given duration in seconds whose samples count up from 0.
+fn ramp(duration: f64) -> Signal {
+  let length = (duration * SAMPLE_RATE as f64).round() as usize;
+
+  Signal::new((0..length).map(|n| n as f32).collect(), SAMPLE_RATE)
+}
+
+fn assert_span(window: &AudioWindow, start: f64, end: f64) {
+  assert!((window.start - start).abs() < 1e-9_f64, "{} starts at {}", window.frame, window.start);
+  assert!((window.end - end).abs() < 1e-9_f64, "{} ends at {}", window.frame, window.end);
+
+  let first = (start * SAMPLE_RATE as f64).round() as usize;
+  let last = (end * SAMPLE_RATE as f64).round() as usize;
+  assert_eq!(window.signal.samples.len(), last - first, "frame {}", window.frame);
+  assert_eq!(window.signal.samples.first().cloned(), (first < last).then_some(first as f32));
+}
+
+#[test]
+fn windows_without_overlap_are_the_frames() {
+  let signal = ramp(1_f64);
+
+  assert_span(&frame_window(&signal, FPS, 0, 0_f64), 0_f64, 0.1_f64);
+  assert_span(&frame_window(&signal, FPS, 3, 0_f64), 0.3_f64, 0.4_f64);
+  assert_span(&frame_window(&signal, FPS, 9, 0_f64), 0.9_f64, 1_f64);
+}
+
+#[test]
+fn overlapping_windows_are_stretched_around_the_frame() {
+  let signal = ramp(1_f64);
+
+  // Half of the window is shared with each neighbour, so the window is two
+  // frames long.
+  let (third, fourth) = (frame_window(&signal, FPS, 3, 0.5_f64), frame_window(&signal, FPS, 4, 0.5_f64));
+  assert_span(&third, 0.25_f64, 0.45_f64);
+  assert_span(&fourth, 0.35_f64, 0.55_f64);
+  assert!((third.end - fourth.start - 0.5_f64 * (third.end - third.start)).abs() < 1e-9_f64);
+}
+
+#[test]
+fn windows_are_clamped_to_the_signal() {
+  let signal = ramp(1_f64);
+
+  assert_span(&frame_window(&signal, FPS, 0, 0.5_f64), 0_f64, 0.15_f64);
+  assert_span(&frame_window(&signal, FPS, 9, 0.5_f64), 0.85_f64, 1_f64);
+
+  // Frames past the end of the signal have no audio.
+  assert_span(&frame_window(&signal, FPS, 12, 0_f64), 1_f64, 1_f64);
+}
+
+#[test]
+fn last_partial_frame_gets_the_rest_of_the_signal() {
+  let windows = frame_windows(&ramp(0.95_f64), FPS, 0_f64);
+
+  assert_eq!(windows.len(), 10);
+  assert_eq!(windows.iter().map(|window| window.frame).collect::<Vec<usize>>(), (0..10).collect::<Vec<usize>>());
+  assert_span(&windows[9], 0.9_f64, 0.95_f64);
+}
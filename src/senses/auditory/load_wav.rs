@@ -0,0 +1,52 @@
+use senses::auditory::hound::{SampleFormat, WavReader};
+use senses::auditory::signal::Signal;
+use senses::error::SensesError;
+use senses::media_source::{MediaKind, MediaSource};
+
+/// Loads a WAV of any channel count, sample rate and sample format that hound
+/// can read (8, 16, 24 or 32 bit integers and 32 bit floats). The channels are
+/// averaged into one and the samples are scaled into range of -1 to 1, float
+/// samples that are not finite are taken for silence. Should the source have a
+/// time range, only that part of the WAV is kept.
+pub fn load_wav(source: &MediaSource) -> Result<Signal, SensesError> {
+  if source.kind() != MediaKind::Wav {
+    return Err(SensesError::UnsupportedMedia(source.path().to_path_buf()));
+  }
+
+  let mut reader = WavReader::open(source.path())?;
+  let spec = reader.spec();
+
+  // Integer samples are scaled by the largest value of their bit depth, 8 bit
+  // samples are already shifted around zero by hound.
+  let interleaved: Vec<f32> = match spec.sample_format {
+    SampleFormat::Float => reader.samples::<f32>()
+      .map(|sample| sample.map(|sample| if sample.is_finite() { sample.clamp(-1_f32, 1_f32) } else { 0_f32 }))
+      .collect::<Result<_, _>>()?,
+    SampleFormat::Int => {
+      let scale = (1_i64 << (spec.bits_per_sample - 1)) as f32;
+
+      reader.samples::<i32>()
+        .map(|sample| sample.map(|sample| sample as f32 / scale))
+        .collect::<Result<_, _>>()?
+    },
+  };
+
+  // Downmixes each frame of interleaved channels into its average.
+  let channels = spec.channels as usize;
+  let samples: Vec<f32> = interleaved
+    .chunks(channels)
+    .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+    .collect();
+
+  let signal = Signal::new(samples, spec.sample_rate);
+
+  Ok(match source.time_range() {
+    None => signal,
+    Some(range) => {
+      let start = signal.sample_at(range.start);
+      let end = signal.sample_at(range.end).max(start);
+
+      Signal::new(signal.samples[start..end].to_vec(), signal.sample_rate)
+    },
+  })
+}
@@ -0,0 +1,135 @@
+//! Tests of loading WAVs of each sample format and channel count that hound
+//! can write.
+
+use std::env;
+use std::f32;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use senses::auditory::hound::{SampleFormat, WavSpec, WavWriter};
+use senses::auditory::load_wav::load_wav;
+use senses::auditory::signal::Signal;
+use senses::media_source::{MediaKind, MediaSource};
+
+/// Loading keeps the rate of the WAV, which is not the default one.
+const SAMPLE_RATE: u32 = 22050;
+
+fn spec(channels: u16, bits_per_sample: u16, sample_format: SampleFormat) -> WavSpec {
+  WavSpec { channels, sample_rate: SAMPLE_RATE, bits_per_sample, sample_format }
+}
+
+/// Writes the interleaved integer samples into a WAV and loads it.
+fn load_ints(name: &str, spec: WavSpec, samples: &[i32]) -> Signal {
+  let path = env::temp_dir().join(format!("load-{}-{}.wav", name, process::id()));
+  let mut writer = WavWriter::create(&path, spec).unwrap();
+
+  for sample in samples {
+    writer.write_sample(*sample).unwrap();
+  }
+  writer.finalize().unwrap();
+
+  load(path)
+}
+
+/// Writes the interleaved float samples into a WAV and loads it.
+fn load_floats(name: &str, spec: WavSpec, samples: &[f32]) -> Signal {
+  let path = env::temp_dir().join(format!("load-{}-{}.wav", name, process::id()));
+  let mut writer = WavWriter::create(&path, spec).unwrap();
+
+  for sample in samples {
+    writer.write_sample(*sample).unwrap();
+  }
+  writer.finalize().unwrap();
+
+  load(path)
+}
+
+fn load(path: PathBuf) -> Signal {
+  let signal = load_wav(&MediaSource::new(path.clone(), MediaKind::Wav)).unwrap();
+  fs::remove_file(&path).unwrap();
+
+  signal
+}
+
+fn assert_samples(signal: &Signal, expected: &[f32], tolerance: f32, case: &str) {
+  assert_eq!(signal.sample_rate, SAMPLE_RATE, "{}", case);
+  assert_eq!(signal.samples.len(), expected.len(), "{}", case);
+
+  for (found, expected) in signal.samples.iter().zip(expected.iter()) {
+    assert!(
+      (found - expected).abs() <= tolerance,
+      "{}: {:?} instead of {:?}",
+      case,
+      signal.samples,
+      expected,
+    );
+  }
+}
+
+#[test]
+fn integer_samples_are_scaled_by_their_bit_depth() {
+  for bits in [8_u16, 16, 24, 32].iter() {
+    let max = ((1_i64 << (bits - 1)) - 1) as i32;
+    let min = -max - 1;
+    let half = (1_i64 << (bits - 2)) as i32;
+
+    let signal = load_ints(&format!("mono-{}", bits), spec(1, *bits, SampleFormat::Int), &[
+      max, min, half, 0,
+    ]);
+
+    // The largest value is a step below 1.
+    let step = 1_f32 / (1_i64 << (bits - 1)) as f32;
+    assert_samples(&signal, &[1_f32, -1_f32, 0.5_f32, 0_f32], step, &format!("{} bit", bits));
+    assert!(signal.samples.iter().all(|sample| (-1_f32..=1_f32).contains(sample)));
+  }
+}
+
+#[test]
+fn integer_channels_are_averaged_into_mono() {
+  for bits in [8_u16, 16, 24, 32].iter() {
+    let max = ((1_i64 << (bits - 1)) - 1) as i32;
+    let min = -max - 1;
+    let half = (1_i64 << (bits - 2)) as i32;
+
+    // Left and right samples of each frame.
+    let signal = load_ints(&format!("stereo-{}", bits), spec(2, *bits, SampleFormat::Int), &[
+      max, max,
+      min, min,
+      max, min,
+      half, 0,
+    ]);
+
+    let step = 1_f32 / (1_i64 << (bits - 1)) as f32;
+    assert_samples(&signal, &[1_f32, -1_f32, 0_f32, 0.25_f32], step, &format!("{} bit", bits));
+  }
+}
+
+#[test]
+fn float_samples_are_clamped_and_averaged_into_mono() {
+  let mono = load_floats("float-mono", spec(1, 32, SampleFormat::Float), &[
+    0.5_f32, -0.25_f32, 2_f32, -3_f32,
+  ]);
+  assert_samples(&mono, &[0.5_f32, -0.25_f32, 1_f32, -1_f32], 0_f32, "mono");
+
+  let stereo = load_floats("float-stereo", spec(2, 32, SampleFormat::Float), &[
+    0.5_f32, -0.5_f32,
+    1_f32, 0.5_f32,
+    2_f32, 2_f32,
+  ]);
+  assert_samples(&stereo, &[0_f32, 0.75_f32, 1_f32], 0_f32, "stereo");
+}
+
+#[test]
+fn float_samples_that_are_not_finite_are_silence() {
+  let mono = load_floats("nan-mono", spec(1, 32, SampleFormat::Float), &[
+    f32::NAN, f32::INFINITY, f32::NEG_INFINITY, 0.5_f32,
+  ]);
+  assert_samples(&mono, &[0_f32, 0_f32, 0_f32, 0.5_f32], 0_f32, "mono");
+
+  // Only the channel that is not finite is silent.
+  let stereo = load_floats("nan-stereo", spec(2, 32, SampleFormat::Float), &[
+    f32::NAN, 0.5_f32,
+  ]);
+  assert_samples(&stereo, &[0.25_f32], 0_f32, "stereo");
+}
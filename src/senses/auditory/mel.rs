@@ -0,0 +1,70 @@
+use senses::auditory::config::AuditoryConfig;
+use senses::auditory::spectrogram::Spectrogram;
+
+/// Smallest energy that is taken a logarithm of. Keeps silent bands finite.
+const ENERGY_FLOOR: f32 = 1e-10_f32;
+
+/// Converts frequency in Hz to the mel scale, on which equal distances sound
+/// equally far apart in pitch.
+pub fn hz_to_mel(hz: f32) -> f32 {
+  2595_f32 * (1_f32 + hz / 700_f32).log10()
+}
+
+pub fn mel_to_hz(mel: f32) -> f32 {
+  700_f32 * (10_f32.powf(mel / 2595_f32) - 1_f32)
+}
+
+/// Builds triangular filters spaced evenly on the mel scale between the
+/// configured frequencies. Each filter rises from the centre of the previous
+/// one to its own centre and falls to the centre of the next one. Returns the
+/// weight of each bin of the spectrogram for each filter.
+///
+/// Low filters can be narrower than a bin when the frames are short, such
+/// filters miss all bins and their energy is always zero.
+pub fn mel_filterbank(spectrogram: &Spectrogram, config: &AuditoryConfig) -> Vec<Vec<f32>> {
+  let nyquist = spectrogram.sample_rate as f32 / 2_f32;
+  let min_mel = hz_to_mel(config.min_frequency.max(0_f32));
+  let max_mel = hz_to_mel(config.max_frequency.unwrap_or(nyquist).min(nyquist));
+
+  // Edges of the filters, two more than there are filters.
+  let edges: Vec<f32> = (0..(config.mel_bands + 2))
+    .map(|index| mel_to_hz(min_mel + (max_mel - min_mel) * index as f32 / (config.mel_bands + 1) as f32))
+    .collect();
+
+  (0..config.mel_bands).map(|band| {
+    let (lower, centre, upper) = (edges[band], edges[band + 1], edges[band + 2]);
+
+    (0..spectrogram.bins()).map(|bin| {
+      let frequency = spectrogram.frequency(bin);
+
+      if frequency <= lower || frequency >= upper {
+        0_f32
+      } else if frequency <= centre {
+        (frequency - lower) / (centre - lower)
+      } else {
+        (upper - frequency) / (upper - centre)
+      }
+    }).collect()
+  }).collect()
+}
+
+/// Energy of each mel band in each frame of the spectrogram, calculated from
+/// its power.
+pub fn mel_spectrogram(spectrogram: &Spectrogram, config: &AuditoryConfig) -> Vec<Vec<f32>> {
+  let filterbank = mel_filterbank(spectrogram, config);
+  let power = spectrogram.power();
+
+  power.frames.iter().map(
+    |frame| filterbank.iter().map(
+      |filter| filter.iter().zip(frame.iter()).map(|(weight, bin)| weight * bin).sum()
+    ).collect()
+  ).collect()
+}
+
+/// Natural logarithm of the mel band energies. Loudness is perceived roughly
+/// logarithmically, therefore log-mel is closer to what is heard.
+pub fn log_mel(mel: &[Vec<f32>]) -> Vec<Vec<f32>> {
+  mel.iter().map(
+    |frame| frame.iter().map(|energy| energy.max(ENERGY_FLOOR).ln()).collect()
+  ).collect()
+}
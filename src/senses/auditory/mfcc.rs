@@ -0,0 +1,110 @@
+use std::f32::consts::PI;
+use std::fs;
+use std::path::Path;
+
+use senses::auditory::config::AuditoryConfig;
+use senses::auditory::mel::{log_mel, mel_spectrogram};
+use senses::auditory::spectrogram::Spectrogram;
+use senses::error::SensesError;
+
+/// Number of frames on each side that the slope of deltas is fitted over.
+const DELTA_WIDTH: usize = 2;
+
+/// Cepstral coefficients of each frame of a spectrogram together with how
+/// fast they change (deltas) and how fast the change changes (delta-deltas).
+#[derive(Clone, Debug, PartialEq)]
+pub struct MfccFeatures {
+
+  /// Time of the centre of each frame in seconds.
+  pub times: Vec<f64>,
+
+  pub coefficients: Vec<Vec<f32>>,
+
+  pub deltas: Vec<Vec<f32>>,
+
+  pub delta_deltas: Vec<Vec<f32>>,
+
+}
+
+impl MfccFeatures {
+
+  /// Coefficients, deltas and delta-deltas of the frame joined into a single
+  /// vector, e.g. the weights of a neuron.
+  pub fn vector(&self, frame: usize) -> Vec<f32> {
+    let mut vector = self.coefficients[frame].clone();
+    vector.extend(self.deltas[frame].iter());
+    vector.extend(self.delta_deltas[frame].iter());
+
+    vector
+  }
+
+  /// Feature vector of every frame, see `vector`.
+  pub fn vectors(&self) -> Vec<Vec<f32>> {
+    (0..self.times.len()).map(|frame| self.vector(frame)).collect()
+  }
+
+  /// Writes a CSV with a row for each frame, which starts with the time of the
+  /// frame followed by its feature vector.
+  pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SensesError> {
+    let mut csv: String = String::new();
+
+    for (time, vector) in self.times.iter().zip(self.vectors()) {
+      let values: Vec<String> = vector.iter().map(|value| value.to_string()).collect();
+      csv.push_str(&format!("{},{}\n", time, values.join(",")));
+    }
+
+    fs::write(path, csv)?;
+
+    Ok(())
+  }
+
+}
+
+/// Calculates mel frequency cepstral coefficients of each frame. The log-mel
+/// energies of a frame are decorrelated with a discrete cosine transform, the
+/// first coefficients describe the overall shape of the spectrum (timbre)
+/// while ignoring its fine detail (pitch).
+pub fn mfcc(spectrogram: &Spectrogram, config: &AuditoryConfig) -> MfccFeatures {
+  let coefficients = dct(&log_mel(&mel_spectrogram(spectrogram, config)), config.mfcc_coefficients);
+  let deltas = deltas(&coefficients);
+  let delta_deltas = self::deltas(&deltas);
+
+  MfccFeatures {
+    times: (0..coefficients.len()).map(|frame| spectrogram.time(frame)).collect(),
+    coefficients,
+    deltas,
+    delta_deltas,
+  }
+}
+
+/// Orthonormal type II discrete cosine transform of each frame, keeping the
+/// first given number of coefficients.
+pub fn dct(frames: &[Vec<f32>], coefficients: usize) -> Vec<Vec<f32>> {
+  frames.iter().map(|frame| {
+    let length = frame.len() as f32;
+
+    (0..coefficients).map(|k| {
+      let scale = if k == 0 { (1_f32 / length).sqrt() } else { (2_f32 / length).sqrt() };
+
+      scale * frame.iter().enumerate().map(
+        |(n, value)| value * (PI * k as f32 * (n as f32 + 0.5_f32) / length).cos()
+      ).sum::<f32>()
+    }).collect()
+  }).collect()
+}
+
+/// Slope of each feature over time fitted by least squares over DELTA_WIDTH
+/// frames on each side. Frames beyond the ends repeat the first and last one.
+pub fn deltas(frames: &[Vec<f32>]) -> Vec<Vec<f32>> {
+  let last = frames.len() as isize - 1;
+  let denominator = 2_f32 * (1..=DELTA_WIDTH).map(|n| (n * n) as f32).sum::<f32>();
+  let frame = |index: isize| &frames[index.max(0).min(last) as usize];
+
+  (0..frames.len() as isize).map(|index| {
+    (0..frames[index as usize].len()).map(|feature| {
+      (1..=DELTA_WIDTH as isize).map(|n| {
+        n as f32 * (frame(index + n)[feature] - frame(index - n)[feature])
+      }).sum::<f32>() / denominator
+    }).collect()
+  }).collect()
+}
@@ -0,0 +1,151 @@
+//! Tests of the mel filterbank and of the cepstral features built on it.
+
+use std::env;
+use std::fs;
+use std::process;
+
+use senses::auditory::config::AuditoryConfig;
+use senses::auditory::mel::{hz_to_mel, mel_filterbank, mel_to_hz};
+use senses::auditory::mfcc::{dct, deltas, mfcc};
+use senses::auditory::signal::Signal;
+use senses::auditory::spectrogram::{Spectrogram, SpectrumScale};
+use senses::auditory::stft::stft;
+
+/// Silent spectrogram of given frame length, only its layout matters.
+fn spectrogram(frame_length: usize) -> Spectrogram {
+  Spectrogram {
+    frames: vec!(vec!(0_f32; frame_length / 2 + 1)),
+    scale: SpectrumScale::Magnitude,
+    sample_rate: 16000,
+    frame_length,
+    hop: frame_length / 2,
+  }
+}
+
+#[test]
+fn mel_scale_round_trips() {
+  assert!((hz_to_mel(1000_f32) - 1000_f32).abs() < 0.5_f32);
+
+  for hz in [0_f32, 20_f32, 440_f32, 8000_f32].iter() {
+    assert!((mel_to_hz(hz_to_mel(*hz)) - hz).abs() < 0.01_f32, "{} Hz", hz);
+  }
+}
+
+#[test]
+fn filterbank_stays_between_its_edges() {
+  let spectrogram = spectrogram(1024);
+  let config = AuditoryConfig {
+    mel_bands: 20,
+    min_frequency: 300_f32,
+    max_frequency: Some(4000_f32),
+    ..AuditoryConfig::default()
+  };
+  let filterbank = mel_filterbank(&spectrogram, &config);
+
+  assert_eq!(filterbank.len(), 20);
+
+  let step = (hz_to_mel(4000_f32) - hz_to_mel(300_f32)) / 21_f32;
+  let (first_centre, last_centre) = (mel_to_hz(hz_to_mel(300_f32) + step), mel_to_hz(hz_to_mel(4000_f32) - step));
+
+  for bin in 0..spectrogram.bins() {
+    let frequency = spectrogram.frequency(bin);
+    let total: f32 = filterbank.iter().map(|filter| filter[bin]).sum();
+
+    if frequency <= 300_f32 || frequency >= 4000_f32 {
+      assert_eq!(total, 0_f32, "{} Hz is outside of the bank", frequency);
+    }
+
+    // Neighbouring triangles cross at a half, between the first and the last
+    // centre they add up to one.
+    if frequency >= first_centre && frequency <= last_centre {
+      assert!((total - 1_f32).abs() < 1e-4_f32, "{} Hz adds up to {}", frequency, total);
+    }
+  }
+
+  for filter in filterbank.iter() {
+    assert!(filter.iter().all(|weight| *weight >= 0_f32 && *weight <= 1_f32));
+  }
+}
+
+#[test]
+fn filterbank_ends_at_nyquist_by_default() {
+  let spectrogram = spectrogram(512);
+  let config = AuditoryConfig { mel_bands: 10, ..AuditoryConfig::default() };
+  let filterbank = mel_filterbank(&spectrogram, &config);
+
+  // Last filter falls to zero at the Nyquist frequency, up to the rounding of
+  // the conversion to mels and back.
+  let last = &filterbank[9];
+  assert!(last[spectrogram.bins() - 1] < 1e-5_f32);
+  assert!(last[spectrogram.bins() - 2] > 0_f32);
+}
+
+#[test]
+fn dct_is_orthonormal() {
+  let length = 8;
+  let basis: Vec<Vec<f32>> = (0..length)
+    .map(|index| (0..length).map(|n| if n == index { 1_f32 } else { 0_f32 }).collect())
+    .collect();
+
+  // Transforms of the unit vectors are the columns of the DCT matrix.
+  let columns = dct(&basis, length);
+
+  for a in 0..length {
+    for b in 0..length {
+      let product: f32 = (0..length).map(|k| columns[a][k] * columns[b][k]).sum();
+      let expected = if a == b { 1_f32 } else { 0_f32 };
+
+      assert!((product - expected).abs() < 1e-5_f32, "columns {} and {} give {}", a, b, product);
+    }
+  }
+
+  // Constant frame has all its energy in the first coefficient.
+  let constant = dct(&[vec!(2_f32; length)], 3);
+  assert!((constant[0][0] - 2_f32 * (length as f32).sqrt()).abs() < 1e-5_f32);
+  assert!(constant[0][1].abs() < 1e-5_f32 && constant[0][2].abs() < 1e-5_f32);
+}
+
+#[test]
+fn deltas_of_a_ramp_are_its_slope() {
+  let ramp: Vec<Vec<f32>> = (0..8).map(|frame| vec!(frame as f32, 5_f32)).collect();
+  let found = deltas(&ramp);
+
+  // Frames beyond the ends repeat the first and the last one, which flattens
+  // the slope at the borders.
+  let expected = [0.5_f32, 0.8_f32, 1_f32, 1_f32, 1_f32, 1_f32, 0.8_f32, 0.5_f32];
+
+  for (frame, slope) in expected.iter().enumerate() {
+    assert!((found[frame][0] - slope).abs() < 1e-6_f32, "frame {} has {}", frame, found[frame][0]);
+    assert_eq!(found[frame][1], 0_f32);
+  }
+
+  assert_eq!(deltas(&[vec!(3_f32)]), vec!(vec!(0_f32)));
+}
+
+#[test]
+fn csv_has_time_and_features_of_each_frame() {
+  let config = AuditoryConfig { frame_length: 512, hop: 256, mfcc_coefficients: 5, ..AuditoryConfig::default() };
+  let samples = (0..4000).map(|n| (n as f32 * 0.05_f32).sin()).collect();
+  let spectrogram = stft(&Signal::new(samples, 16000), &config);
+  let features = mfcc(&spectrogram, &config);
+
+  let path = env::temp_dir().join(format!("mfcc-{}.csv", process::id()));
+  features.save(&path).unwrap();
+  let csv = fs::read_to_string(&path).unwrap();
+  fs::remove_file(&path).unwrap();
+
+  let rows: Vec<Vec<f64>> = csv.lines()
+    .map(|line| line.split(',').map(|value| value.parse().unwrap()).collect())
+    .collect();
+
+  assert_eq!(rows.len(), spectrogram.frames.len());
+
+  for (frame, row) in rows.iter().enumerate() {
+    // Time, coefficients, deltas and delta-deltas.
+    assert_eq!(row.len(), 1 + 3 * 5);
+    assert!((row[0] - spectrogram.time(frame)).abs() < 1e-9_f64);
+    assert_eq!(row[1] as f32, features.coefficients[frame][0]);
+    assert_eq!(row[6] as f32, features.deltas[frame][0]);
+    assert_eq!(row[11] as f32, features.delta_deltas[frame][0]);
+  }
+}
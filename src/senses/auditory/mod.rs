@@ -1,36 +1,53 @@
 extern crate hound;
-
-use std::fs;
-use std::collections::HashMap;
-
-pub fn sound() {
-  let mut reader = hound::WavReader::open("data/debug-1/audio/note-e.wav").unwrap();
-
-  let samples = reader.samples::<i16>();
-  let mut sponge: HashMap<i16, u32> = HashMap::new();
-
-  for sample in samples {
-    let key = sample.unwrap();
-    let value = sponge.get(&key).unwrap_or(&0) + 1;
-    sponge.insert(key, value);
-  }
-
-  let mut csv: String = String::new();
-
-  for (key, value) in sponge.iter() {
-    csv.push_str(&format!("{},{}\n", key, value));
-  }
-
-  fs::write("test.csv", csv).expect("Unable to write file");
-}
-
-/*
-let mut csv = String::new();
-
-for sample in samples {
-  csv.push_str(&("\n".to_owned() + &sample.unwrap().to_string()));
-}
-
-fs::write("test.csv", csv).expect("Unable to write file");
-*/
-
+extern crate image;
+extern crate rustfft;
+
+mod config;
+mod signal;
+mod load_wav;
+mod resample;
+mod spectrogram;
+mod stft;
+mod mel;
+mod mfcc;
+mod pitch;
+mod onsets;
+mod auditory_object;
+mod activity;
+mod spectral_objects;
+mod frame_windows;
+mod statistics;
+
+#[cfg(test)]
+mod sweep_tests;
+#[cfg(test)]
+mod load_wav_tests;
+#[cfg(test)]
+mod stft_tests;
+#[cfg(test)]
+mod mfcc_tests;
+#[cfg(test)]
+mod pitch_tests;
+#[cfg(test)]
+mod onsets_tests;
+#[cfg(test)]
+mod activity_tests;
+#[cfg(test)]
+mod spectral_objects_tests;
+#[cfg(test)]
+mod frame_windows_tests;
+#[cfg(test)]
+mod statistics_tests;
+
+pub use senses::auditory::config::{AuditoryConfig, Window};
+pub use senses::auditory::load_wav::load_wav;
+pub use senses::auditory::resample::resample;
+pub use senses::auditory::stft::stft;
+pub use senses::auditory::mfcc::mfcc;
+pub use senses::auditory::pitch::track_pitch;
+pub use senses::auditory::onsets::segment_events;
+pub use senses::auditory::auditory_object::{save_events, AuditoryObject};
+pub use senses::auditory::activity::detect_activity;
+pub use senses::auditory::frame_windows::{frame_window, frame_windows, save_window_features, WindowFeatures};
+pub use senses::auditory::spectral_objects::{find_spectral_objects, save_spectral_objects};
+pub use senses::auditory::statistics::signal_statistics;
@@ -0,0 +1,117 @@
+use senses::auditory::auditory_object::AuditoryObject;
+use senses::auditory::config::AuditoryConfig;
+use senses::auditory::spectrogram::{Spectrogram, SpectrumScale};
+
+/// Compression of the magnitudes before the flux is calculated. Without it,
+/// the flux would only follow the loudest bins.
+const COMPRESSION: f32 = 100_f32;
+
+/// Frames on each side of an onset that have to have lower flux than it.
+const MAX_WINDOW: usize = 3;
+
+/// Frames before and after an onset whose mean flux it has to exceed by the
+/// threshold.
+const MEAN_WINDOW: (usize, usize) = (8, 3);
+
+/// Sound ends once the energy of its frames falls this many decibels below
+/// the loudest frame of the signal.
+const SILENCE: f32 = 50_f32;
+
+/// Spectral flux of each frame: how much the log compressed magnitudes of the
+/// bins rose since the previous frame. Falling bins are ignored, because only
+/// new sounds are of interest. The first frame is compared to silence. The
+/// flux is scaled so that the highest one is 1.
+pub fn spectral_flux(spectrogram: &Spectrogram) -> Vec<f32> {
+  let magnitudes: Vec<Vec<f32>> = spectrogram.frames.iter().map(|frame| {
+    frame.iter().map(|bin| {
+      let magnitude = match spectrogram.scale {
+        SpectrumScale::Magnitude => *bin,
+        SpectrumScale::Power => bin.sqrt(),
+      };
+
+      (1_f32 + COMPRESSION * magnitude).ln()
+    }).collect()
+  }).collect();
+
+  let silence: Vec<f32> = vec!(0_f32; spectrogram.bins());
+
+  let flux: Vec<f32> = magnitudes.iter().enumerate().map(|(index, frame)| {
+    let previous = if index == 0 { &silence } else { &magnitudes[index - 1] };
+
+    frame.iter().zip(previous.iter())
+      .map(|(bin, previous)| (bin - previous).max(0_f32))
+      .sum()
+  }).collect();
+
+  let highest = flux.iter().fold(0_f32, |max, value| max.max(*value));
+
+  if highest > 0_f32 {
+    flux.iter().map(|value| value / highest).collect()
+  } else {
+    flux
+  }
+}
+
+/// Picks the frames where a sound starts. A frame is an onset if its flux is
+/// the highest in its neighbourhood, it is above the local mean flux by the
+/// threshold and enough time passed since the previous onset.
+pub fn detect_onsets(spectrogram: &Spectrogram, config: &AuditoryConfig) -> Vec<usize> {
+  let flux = spectral_flux(spectrogram);
+  let min_gap = (config.min_onset_gap * spectrogram.sample_rate as f64 / spectrogram.hop as f64).ceil() as usize;
+  let window = |index: usize, before: usize, after: usize| {
+    &flux[index.saturating_sub(before)..(index + after + 1).min(flux.len())]
+  };
+
+  let mut onsets: Vec<usize> = Vec::new();
+
+  for (index, value) in flux.iter().enumerate() {
+    let is_peak = window(index, MAX_WINDOW, MAX_WINDOW).iter().all(|other| other <= value);
+
+    let neighbourhood = window(index, MEAN_WINDOW.0, MEAN_WINDOW.1);
+    let mean = neighbourhood.iter().sum::<f32>() / neighbourhood.len() as f32;
+
+    let is_apart = onsets.last().is_none_or(|last| index - last >= min_gap);
+
+    if *value > 0_f32 && is_peak && *value >= mean + config.onset_threshold && is_apart {
+      onsets.push(index);
+    }
+  }
+
+  onsets
+}
+
+/// Splits the signal into sounds. Each sound starts at an onset and lasts
+/// until the next onset or until its frames fall silent, whichever comes
+/// first. Sounds shorter than the minimal event duration are dropped, as those
+/// are clicks, such as the one at the abrupt end of a note, rather than sounds
+/// of their own.
+pub fn segment_events(spectrogram: &Spectrogram, config: &AuditoryConfig) -> Vec<AuditoryObject> {
+  let onsets = detect_onsets(spectrogram, config);
+  let flux = spectral_flux(spectrogram);
+
+  let energies: Vec<f32> = spectrogram.power().frames.iter()
+    .map(|frame| frame.iter().sum())
+    .collect();
+  let loudest = energies.iter().fold(0_f32, |max, energy| max.max(*energy));
+  let floor = loudest * 10_f32.powf(-SILENCE / 10_f32);
+
+  // Each frame stands for the hop around its centre.
+  let hop_time = spectrogram.hop as f64 / spectrogram.sample_rate as f64;
+
+  onsets.iter().enumerate().map(|(index, onset)| {
+    let next = onsets.get(index + 1).cloned().unwrap_or(energies.len());
+    let end_frame = (onset + 1..next)
+      .find(|frame| energies[*frame] < floor)
+      .unwrap_or(next);
+
+    AuditoryObject {
+      start: (spectrogram.time(*onset) - hop_time / 2_f64).max(0_f64),
+      end: spectrogram.time(end_frame - 1) + hop_time / 2_f64,
+      first_frame: *onset,
+      end_frame,
+      strength: flux[*onset],
+    }
+  })
+    .filter(|event| event.duration() >= config.min_event_duration)
+    .collect()
+}
@@ -0,0 +1,95 @@
+//! Tests of the onset detector and of the sound events on clicks at known
+//! times.
+
+use senses::auditory::config::AuditoryConfig;
+use senses::auditory::onsets::{detect_onsets, segment_events};
+use senses::auditory::signal::Signal;
+use senses::auditory::stft::stft;
+
+const SAMPLE_RATE: u32 = 16000;
+
+/// Second and a half of silence with a single sample click at each time.
+fn clicks(times: &[f64]) -> Signal {
+  let mut samples = vec!(0_f32; 3 * SAMPLE_RATE as usize / 2);
+
+  for time in times {
+    samples[(time * SAMPLE_RATE as f64) as usize] = 1_f32;
+  }
+
+  Signal::new(samples, SAMPLE_RATE)
+}
+
+fn config() -> AuditoryConfig {
+  AuditoryConfig { frame_length: 512, hop: 256, ..AuditoryConfig::default() }
+}
+
+#[test]
+fn clicks_are_found_within_a_hop() {
+  let config = config();
+  let times = [0.2_f64, 0.55_f64, 0.9_f64, 1.2_f64];
+  let spectrogram = stft(&clicks(&times), &config);
+  let onsets = detect_onsets(&spectrogram, &config);
+
+  assert_eq!(onsets.len(), times.len(), "{:?}", onsets);
+
+  let hop_time = config.hop as f64 / SAMPLE_RATE as f64;
+  for (onset, time) in onsets.iter().zip(times.iter()) {
+    let found = spectrogram.time(*onset);
+
+    assert!((found - time).abs() <= hop_time, "click at {} found at {}", time, found);
+  }
+}
+
+#[test]
+fn onsets_closer_than_the_gap_are_merged() {
+  let times = [0.5_f64, 0.6_f64];
+
+  let close = AuditoryConfig { min_onset_gap: 0.2_f64, ..config() };
+  let spectrogram = stft(&clicks(&times), &close);
+  assert_eq!(detect_onsets(&spectrogram, &close).len(), 1);
+
+  let apart = AuditoryConfig { min_onset_gap: 0.05_f64, ..config() };
+  assert_eq!(detect_onsets(&spectrogram, &apart).len(), 2);
+}
+
+#[test]
+fn silence_has_no_onsets() {
+  let config = config();
+  let spectrogram = stft(&clicks(&[]), &config);
+
+  assert!(detect_onsets(&spectrogram, &config).is_empty());
+}
+
+#[test]
+fn event_of_a_click_spans_the_frames_it_is_heard_in() {
+  let config = AuditoryConfig { min_event_duration: 0_f64, ..config() };
+  let times = [0.2_f64, 0.55_f64, 0.9_f64, 1.2_f64];
+  let events = segment_events(&stft(&clicks(&times), &config), &config);
+
+  assert_eq!(events.len(), times.len(), "{:?}", events);
+
+  // A frame is two hops long, therefore the click is heard in two frames at
+  // most and each frame stands for a hop.
+  let hop_time = config.hop as f64 / SAMPLE_RATE as f64;
+  for (event, time) in events.iter().zip(times.iter()) {
+    assert!(event.start <= *time && *time <= event.end, "click at {} in {:?}", time, event);
+    assert!(event.duration() >= hop_time - 1e-9, "{:?}", event);
+    assert!(event.duration() <= 2_f64 * hop_time + 1e-9, "{:?}", event);
+    assert!((event.duration() - (event.end_frame - event.first_frame) as f64 * hop_time).abs() < 1e-9);
+  }
+}
+
+#[test]
+fn events_shorter_than_the_minimal_duration_are_dropped() {
+  let times = [0.2_f64, 0.55_f64, 0.9_f64, 1.2_f64];
+
+  // The clicks are shorter than the default minimal duration.
+  let config = config();
+  let spectrogram = stft(&clicks(&times), &config);
+  assert_eq!(detect_onsets(&spectrogram, &config).len(), times.len());
+  assert!(segment_events(&spectrogram, &config).is_empty());
+
+  // The gap between onsets does not limit how long the events are.
+  let spaced = AuditoryConfig { min_onset_gap: 0.25_f64, min_event_duration: 0_f64, ..config };
+  assert_eq!(segment_events(&spectrogram, &spaced).len(), times.len());
+}
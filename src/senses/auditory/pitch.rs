@@ -0,0 +1,221 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use senses::auditory::config::AuditoryConfig;
+use senses::auditory::signal::Signal;
+use senses::error::SensesError;
+
+/// Frequency of the A above middle C that the notes are tuned to.
+const CONCERT_PITCH: f32 = 440_f32;
+
+const NOTE_NAMES: [&str; 12] = ["C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B"];
+
+/// Musical note closest to a frequency in twelve tone equal temperament.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Note {
+
+  /// Name of the note without the octave, e.g. `C#`.
+  pub name: &'static str,
+
+  /// Octave in scientific pitch notation, the middle C is C4.
+  pub octave: i32,
+
+  /// How far the frequency is from the note in hundredths of a semitone, in
+  /// range of -50 to 50.
+  pub cents: f32,
+
+}
+
+impl Note {
+
+  pub fn from_frequency(frequency: f32) -> Note {
+    // Number of semitones from C-1, A4 is the 69th.
+    let semitones = 69_f32 + 12_f32 * (frequency / CONCERT_PITCH).log2();
+    let closest = semitones.round();
+
+    Note {
+      name: NOTE_NAMES[(closest as i32).rem_euclid(12) as usize],
+      octave: (closest as i32).div_euclid(12) - 1,
+      cents: (semitones - closest) * 100_f32,
+    }
+  }
+
+}
+
+impl fmt::Display for Note {
+
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    write!(formatter, "{}{} {:+} cents", self.name, self.octave, self.cents.round() as i32)
+  }
+
+}
+
+/// Pitch of a single frame of the signal.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PitchFrame {
+
+  /// Time of the centre of the frame in seconds.
+  pub time: f64,
+
+  /// Estimated fundamental frequency in Hz. It is the best guess even if the
+  /// frame is unvoiced, or 0 if the frame does not repeat at all (silence).
+  pub frequency: f32,
+
+  /// How periodic the frame is, from 0 to 1.
+  pub confidence: f32,
+
+  /// Whether the frame is periodic enough to have a pitch.
+  pub voiced: bool,
+
+}
+
+impl PitchFrame {
+
+  /// Note of the frame if it is voiced.
+  pub fn note(&self) -> Option<Note> {
+    if self.voiced {
+      Some(Note::from_frequency(self.frequency))
+    } else {
+      None
+    }
+  }
+
+}
+
+/// Pitch of each frame of a signal as found by `track_pitch`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PitchTrack {
+  pub frames: Vec<PitchFrame>,
+}
+
+impl PitchTrack {
+
+  /// Writes a CSV with a row for each frame with its time, frequency,
+  /// confidence, whether it is voiced and its note.
+  pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SensesError> {
+    let mut csv: String = String::from("time,frequency,confidence,voiced,note\n");
+
+    for frame in self.frames.iter() {
+      csv.push_str(&format!(
+        "{},{},{},{},{}\n",
+        frame.time,
+        frame.frequency,
+        frame.confidence,
+        frame.voiced,
+        frame.note().map_or(String::new(), |note| note.to_string()),
+      ));
+    }
+
+    fs::write(path, csv)?;
+
+    Ok(())
+  }
+
+}
+
+/// Tracks the fundamental frequency of the signal with the YIN algorithm. For
+/// each frame, it finds the smallest lag at which the frame best repeats
+/// itself. The frames have the length and hop of the STFT, the lag is searched
+/// between the periods of the highest and the lowest configured pitch and the
+/// rest of the frame is the window the lags are compared over.
+pub fn track_pitch(signal: &Signal, config: &AuditoryConfig) -> PitchTrack {
+  let rate = signal.sample_rate as f32;
+  let frame_length = config.frame_length;
+
+  // At least half of the frame is left for the comparison window.
+  let max_lag = ((rate / config.min_pitch).ceil() as usize).min(frame_length / 2);
+  let min_lag = ((rate / config.max_pitch).floor() as usize).max(2).min(max_lag);
+  let window = frame_length - max_lag;
+
+  let samples = &signal.samples;
+  let count = if samples.len() <= frame_length {
+    1
+  } else {
+    1 + (samples.len() - frame_length).div_ceil(config.hop)
+  };
+
+  let mut frame: Vec<f32> = vec!(0_f32; frame_length);
+
+  let frames = (0..count).map(|index| {
+    let start = index * config.hop;
+
+    for (n, value) in frame.iter_mut().enumerate() {
+      *value = samples.get(start + n).cloned().unwrap_or(0_f32);
+    }
+
+    let normalized = cumulative_mean_normalized_difference(&frame, window, max_lag);
+    let (lag, dip) = choose_lag(&normalized, min_lag, max_lag, config.pitch_threshold);
+
+    let confidence = (1_f32 - dip).clamp(0_f32, 1_f32);
+
+    PitchFrame {
+      time: (start + frame_length / 2) as f64 / rate as f64,
+      frequency: if confidence > 0_f32 { rate / lag } else { 0_f32 },
+      confidence,
+      voiced: dip < config.pitch_threshold,
+    }
+  }).collect();
+
+  PitchTrack { frames }
+}
+
+/// Squared difference of the frame and itself shifted by each lag, divided by
+/// its mean over the smaller lags. A periodic frame dips close to zero at its
+/// period while the normalisation keeps the small lags from winning.
+fn cumulative_mean_normalized_difference(frame: &[f32], window: usize, max_lag: usize) -> Vec<f32> {
+  let mut normalized: Vec<f32> = vec!(1_f32; max_lag + 1);
+  let mut running_total = 0_f32;
+
+  for lag in 1..(max_lag + 1) {
+    let difference: f32 = (0..window)
+      .map(|n| (frame[n] - frame[n + lag]).powi(2))
+      .sum();
+
+    running_total += difference;
+
+    // Silence has no differences at all, it stays at 1 and is unvoiced.
+    normalized[lag] = if running_total > 0_f32 {
+      difference * lag as f32 / running_total
+    } else {
+      1_f32
+    };
+  }
+
+  normalized
+}
+
+/// Picks the first dip below the threshold, followed down to its minimum. If
+/// there is no such dip, the lowest value is picked instead. The lag is
+/// refined between the samples by fitting a parabola through the neighbours.
+fn choose_lag(normalized: &[f32], min_lag: usize, max_lag: usize, threshold: f32) -> (f32, f32) {
+  let below = (min_lag..(max_lag + 1)).find(|lag| normalized[*lag] < threshold);
+
+  let lag = match below {
+    Some(mut lag) => {
+      while lag < max_lag && normalized[lag + 1] < normalized[lag] {
+        lag += 1;
+      }
+
+      lag
+    },
+    None => (min_lag..(max_lag + 1)).fold(min_lag, |best, lag| {
+      if normalized[lag] < normalized[best] { lag } else { best }
+    }),
+  };
+
+  if lag <= min_lag || lag >= max_lag {
+    return (lag as f32, normalized[lag]);
+  }
+
+  let (before, at, after) = (normalized[lag - 1], normalized[lag], normalized[lag + 1]);
+  let curvature = before - 2_f32 * at + after;
+
+  if curvature <= 0_f32 {
+    return (lag as f32, at);
+  }
+
+  let shift = (before - after) / (2_f32 * curvature);
+
+  (lag as f32 + shift, at - (before - after) * shift / 4_f32)
+}
@@ -0,0 +1,76 @@
+//! Tests of the pitch tracker on a pure tone and on silence.
+
+use std::f32::consts::PI;
+
+use senses::auditory::config::AuditoryConfig;
+use senses::auditory::pitch::{track_pitch, Note};
+use senses::auditory::signal::Signal;
+
+const SAMPLE_RATE: u32 = 44100;
+
+/// Second of a sine of given frequency.
+fn tone(frequency: f32) -> Signal {
+  let samples = (0..SAMPLE_RATE)
+    .map(|n| (2_f32 * PI * frequency * n as f32 / SAMPLE_RATE as f32).sin())
+    .collect();
+
+  Signal::new(samples, SAMPLE_RATE)
+}
+
+#[test]
+fn concert_pitch_is_a4() {
+  let note = Note::from_frequency(440_f32);
+
+  assert_eq!((note.name, note.octave), ("A", 4));
+  assert!(note.cents.abs() < 1e-3_f32);
+}
+
+#[test]
+fn tone_of_440_hz_is_a4() {
+  let config = AuditoryConfig::default();
+  let track = track_pitch(&tone(440_f32), &config);
+
+  // Frames at the end are padded with silence, only whole frames are checked.
+  let whole = 1 + (SAMPLE_RATE as usize - config.frame_length) / config.hop;
+  assert!(track.frames.len() >= whole);
+
+  for frame in track.frames[..whole].iter() {
+    let note = frame.note().unwrap_or_else(|| panic!("{:?} is unvoiced", frame));
+
+    assert!((frame.frequency - 440_f32).abs() < 1_f32, "{:?}", frame);
+    assert!(frame.confidence > 1_f32 - config.pitch_threshold);
+    assert_eq!((note.name, note.octave), ("A", 4));
+  }
+}
+
+#[test]
+fn silence_is_unvoiced() {
+  let config = AuditoryConfig::default();
+  let track = track_pitch(&Signal::new(vec!(0_f32; SAMPLE_RATE as usize), SAMPLE_RATE), &config);
+
+  assert!(!track.frames.is_empty());
+
+  for frame in track.frames.iter() {
+    assert!(!frame.voiced);
+    assert_eq!(frame.frequency, 0_f32);
+    assert_eq!(frame.note(), None);
+  }
+}
+
+#[test]
+fn stricter_threshold_voices_fewer_frames_of_a_noisy_tone() {
+  // Tone mixed with noise does not repeat exactly at its period.
+  let mut signal = tone(440_f32);
+  let mut state = 1_u32;
+  for sample in signal.samples.iter_mut() {
+    state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+    *sample += 0.3_f32 * ((state >> 16) as f32 / 32768_f32 - 1_f32);
+  }
+
+  let strict = AuditoryConfig { pitch_threshold: 0.01_f32, ..AuditoryConfig::default() };
+  let loose = AuditoryConfig { pitch_threshold: 1_f32, ..AuditoryConfig::default() };
+
+  let voiced = |config: &AuditoryConfig| track_pitch(&signal, config).frames.iter().filter(|frame| frame.voiced).count();
+
+  assert!(voiced(&strict) < voiced(&loose));
+}
@@ -0,0 +1,126 @@
+use std::f64::consts::PI;
+
+use senses::auditory::signal::Signal;
+
+/// How many zero crossings of the sinc the filter covers on each side of the
+/// sample. More crossings make the transition band narrower.
+const ZERO_CROSSINGS: usize = 32;
+
+/// Share of the lower Nyquist frequency that is kept. The rest is the
+/// transition band where the filter rolls off.
+const ROLLOFF: f64 = 0.92;
+
+/// Shape parameter of the Kaiser window, roughly 80 dB of stopband
+/// attenuation.
+const KAISER_BETA: f64 = 8.0;
+
+/// Phases of the filter are precomputed only if there are at most this many
+/// of them, otherwise each output sample calculates its own taps.
+const MAX_PHASES: usize = 1024;
+
+/// Converts the signal to given sample rate with a band limited windowed sinc
+/// interpolation. Every output sample lies at a rational position between the
+/// input samples, therefore the filter is a polyphase one: there is a set of
+/// taps for each fractional position. When downsampling, the cutoff is lowered
+/// to the new Nyquist frequency so that nothing above it aliases.
+pub fn resample(signal: &Signal, target_rate: u32) -> Signal {
+  if signal.sample_rate == target_rate || signal.samples.is_empty() {
+    return Signal::new(signal.samples.clone(), target_rate);
+  }
+
+  // Output sample n lies at input position n * down / up.
+  let divisor = gcd(signal.sample_rate as usize, target_rate as usize);
+  let up = target_rate as usize / divisor;
+  let down = signal.sample_rate as usize / divisor;
+
+  // Cutoff relative to the input Nyquist frequency.
+  let cutoff = ROLLOFF * (up as f64 / down as f64).min(1_f64);
+  // The sinc is stretched when the cutoff is lowered, therefore the filter
+  // needs more input samples to cover the same number of zero crossings.
+  let half_width = (ZERO_CROSSINGS as f64 / cutoff).ceil() as usize;
+
+  let phases: Option<Vec<Vec<f32>>> = if up <= MAX_PHASES {
+    Some((0..up).map(|phase| taps(phase as f64 / up as f64, half_width, cutoff)).collect())
+  } else {
+    None
+  };
+
+  let length = (signal.samples.len() * up).div_ceil(down);
+  let input = &signal.samples;
+
+  let samples: Vec<f32> = (0..length).map(|n| {
+    let position = n * down;
+    let (base, phase) = (position / up, position % up);
+
+    let computed;
+    let taps: &Vec<f32> = match phases {
+      Some(ref phases) => &phases[phase],
+      None => {
+        computed = taps(phase as f64 / up as f64, half_width, cutoff);
+        &computed
+      },
+    };
+
+    // Tap k weighs the input sample base - half_width + 1 + k. Samples outside
+    // of the signal are silence.
+    taps.iter().enumerate().fold(0_f32, |total, (k, weight)| {
+      let index = base as isize - half_width as isize + 1 + k as isize;
+
+      if index < 0 || index as usize >= input.len() {
+        total
+      } else {
+        total + weight * input[index as usize]
+      }
+    })
+  }).collect();
+
+  Signal::new(samples, target_rate)
+}
+
+/// Taps of the filter for an output sample that lies given fraction of a
+/// sample after the input sample it is based on.
+fn taps(fraction: f64, half_width: usize, cutoff: f64) -> Vec<f32> {
+  (0..(2 * half_width)).map(|k| {
+    // Distance of the output sample from the input sample the tap weighs.
+    let distance = fraction + half_width as f64 - 1_f64 - k as f64;
+
+    (cutoff * sinc(cutoff * distance) * kaiser(distance / half_width as f64)) as f32
+  }).collect()
+}
+
+fn sinc(x: f64) -> f64 {
+  if x.abs() < 1e-9 {
+    1_f64
+  } else {
+    (PI * x).sin() / (PI * x)
+  }
+}
+
+/// Kaiser window at position in range of -1 to 1, zero outside of it.
+fn kaiser(position: f64) -> f64 {
+  if position.abs() > 1_f64 {
+    return 0_f64;
+  }
+
+  bessel_i0(KAISER_BETA * (1_f64 - position * position).sqrt()) / bessel_i0(KAISER_BETA)
+}
+
+/// Modified Bessel function of the first kind of order zero, summed as a power
+/// series until the terms stop mattering.
+fn bessel_i0(x: f64) -> f64 {
+  let mut total = 1_f64;
+  let mut term = 1_f64;
+  let mut k = 1_f64;
+
+  while term > total * 1e-12 {
+    term *= (x / (2_f64 * k)).powi(2);
+    total += term;
+    k += 1_f64;
+  }
+
+  total
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+  if b == 0 { a } else { gcd(b, a % b) }
+}
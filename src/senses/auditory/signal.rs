@@ -0,0 +1,44 @@
+/// Mono audio with samples normalised to range of -1 to 1. Every WAV is
+/// loaded into this form, whatever its channels and sample format, so that the
+/// analysis only ever deals with one kind of signal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Signal {
+
+  /// Samples in the order they were recorded.
+  pub samples: Vec<f32>,
+
+  /// How many samples there are in one second.
+  pub sample_rate: u32,
+
+}
+
+impl Signal {
+
+  pub fn new(samples: Vec<f32>, sample_rate: u32) -> Signal {
+    Signal { samples, sample_rate }
+  }
+
+  /// Length of the signal in seconds.
+  pub fn duration(&self) -> f64 {
+    self.samples.len() as f64 / self.sample_rate as f64
+  }
+
+  /// Index of the sample at given time in seconds, clamped into the signal.
+  pub fn sample_at(&self, time: f64) -> usize {
+    ((time.max(0_f64) * self.sample_rate as f64).round() as usize).min(self.samples.len())
+  }
+
+}
+
+/// Share of neighbouring samples that have different signs.
+pub fn zero_crossing_rate(samples: &[f32]) -> f32 {
+  if samples.len() < 2 {
+    return 0_f32;
+  }
+
+  let crossings = samples.windows(2)
+    .filter(|pair| (pair[0] >= 0_f32) != (pair[1] >= 0_f32))
+    .count();
+
+  crossings as f32 / (samples.len() - 1) as f32
+}
@@ -0,0 +1,126 @@
+use std::fs;
+use std::path::Path;
+
+use senses::error::SensesError;
+use senses::auditory::spectrogram::Spectrogram;
+use senses::visual::{cell_to_pixel, find_highlights, intensity_heat_map, VisualConfig, VisualObject};
+
+/// Salient blob of the spectrogram, such as a whistle or a bark, found by the
+/// same pipeline as the objects of a frame. It spans a stretch of time and a
+/// band of frequencies.
+#[derive(Clone, Debug)]
+pub struct SpectralObject {
+
+  /// Time the blob starts at in seconds.
+  pub start: f64,
+
+  /// Time the blob ends at in seconds.
+  pub end: f64,
+
+  /// Lowest frequency of the blob in Hz.
+  pub low_frequency: f32,
+
+  /// Highest frequency of the blob in Hz.
+  pub high_frequency: f32,
+
+  /// Cells of the heat map of the spectrogram the blob covers. Its x axis is
+  /// time and its y axis is frequency going up from 0 Hz.
+  pub highlight: VisualObject,
+
+}
+
+/// Finds salient time-frequency blobs in the spectrogram. The spectrogram is
+/// treated as a picture whose brightness is the loudness of the bins in
+/// decibels, see `Spectrogram::brightness`, everything quieter than the mean
+/// is background. It then goes through the heat map, the cellular automaton
+/// and the highlight extraction of the visual pipeline, so that a sound is a
+/// highlight just as an object of a frame is. Each of the leaf objects is
+/// converted back into time and frequency.
+pub fn find_spectral_objects(spectrogram: &Spectrogram, config: &VisualConfig) -> Vec<SpectralObject> {
+  let cell_size = config.cell_size;
+  let (frames, bins) = (spectrogram.frames.len(), spectrogram.bins());
+
+  if frames == 0 {
+    return Vec::new();
+  }
+
+  let brightness = spectrogram.brightness();
+
+  let mean = (0..frames)
+    .flat_map(|frame| (0..bins).map(move |bin| (frame, bin)))
+    .map(|(frame, bin)| brightness[frame][bin])
+    .sum::<f32>() / (frames * bins) as f32;
+
+  // The grid is padded with silence so that it splits into whole cells.
+  let round_up = |length: usize| {
+    let cell_size = cell_size as usize;
+
+    cell_size * length.div_ceil(cell_size).max(1)
+  };
+
+  let intensities: Vec<Vec<f32>> = (0..round_up(bins)).map(|bin| {
+    (0..round_up(frames)).map(|frame| {
+      if frame >= frames || bin >= bins {
+        return 0_f32;
+      }
+
+      let value = brightness[frame][bin];
+      if value > mean { value } else { 0_f32 }
+    }).collect()
+  }).collect();
+
+  let (heat_map, heat_max, heat_mean) = intensity_heat_map(&intensities, cell_size);
+  let mut highlights = find_highlights(heat_map, heat_max, heat_mean, &config.splitting);
+
+  let hop_time = spectrogram.hop as f64 / spectrogram.sample_rate as f64;
+
+  highlights.iter_mut()
+    .flat_map(|highlight| highlight.leaves_mut())
+    .filter_map(|leaf| {
+      let (lower, higher) = leaf.size()?;
+
+      // Frames and bins from the first pixel of the lower cell up to the last
+      // pixel of the higher one.
+      let first = cell_to_pixel(leaf.reference + lower, cell_size);
+      let last = cell_to_pixel(leaf.reference + higher, cell_size);
+      let end_frame = ((last.x + cell_size / 2) as usize).min(frames);
+      let end_bin = ((last.y + cell_size / 2) as usize).min(bins);
+
+      if first.x as usize >= end_frame || first.y as usize >= end_bin {
+        return None;
+      }
+
+      Some(SpectralObject {
+        start: (spectrogram.time(first.x as usize) - hop_time / 2_f64).max(0_f64),
+        end: spectrogram.time(end_frame - 1) + hop_time / 2_f64,
+        low_frequency: spectrogram.frequency(first.y as usize),
+        high_frequency: spectrogram.frequency(end_bin - 1),
+        highlight: leaf.clone(),
+      })
+    })
+    .collect()
+}
+
+/// Writes a CSV with the time span, the frequency band and the number of cells
+/// of each object.
+pub fn save_spectral_objects<P: AsRef<Path>>(
+  objects: &[SpectralObject],
+  path: P,
+) -> Result<(), SensesError> {
+  let mut csv: String = String::from("start,end,low_frequency,high_frequency,cells\n");
+
+  for object in objects {
+    csv.push_str(&format!(
+      "{},{},{},{},{}\n",
+      object.start,
+      object.end,
+      object.low_frequency,
+      object.high_frequency,
+      object.highlight.points.len(),
+    ));
+  }
+
+  fs::write(path, csv)?;
+
+  Ok(())
+}
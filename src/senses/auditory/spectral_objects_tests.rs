@@ -0,0 +1,61 @@
+//! Tests of finding the blobs of a spectrogram on a tone that starts and ends
+//! at known times.
+
+use std::f32::consts::PI;
+
+use senses::auditory::config::AuditoryConfig;
+use senses::auditory::signal::Signal;
+use senses::auditory::spectral_objects::find_spectral_objects;
+use senses::auditory::stft::stft;
+use senses::visual::VisualConfig;
+
+const SAMPLE_RATE: u32 = 8000;
+
+/// Two seconds of silence with a tone of given frequency from 0.5 to 1.5
+/// seconds.
+fn tone_burst(frequency: f32) -> Signal {
+  let samples = (0..2 * SAMPLE_RATE as usize).map(|n| {
+    let time = n as f32 / SAMPLE_RATE as f32;
+
+    if (0.5_f32..1.5_f32).contains(&time) {
+      (2_f32 * PI * frequency * time).sin()
+    } else {
+      0_f32
+    }
+  }).collect();
+
+  Signal::new(samples, SAMPLE_RATE)
+}
+
+fn configs() -> (AuditoryConfig, VisualConfig) {
+  (
+    AuditoryConfig { frame_length: 256, hop: 128, ..AuditoryConfig::default() },
+    VisualConfig { cell_size: 4, ..VisualConfig::default() },
+  )
+}
+
+#[test]
+fn tone_is_found_at_its_time_and_frequency() {
+  let (auditory, visual) = configs();
+  let spectrogram = stft(&tone_burst(1000_f32), &auditory);
+  let objects = find_spectral_objects(&spectrogram, &visual);
+
+  assert_eq!(objects.len(), 1, "{:?}", objects);
+  let object = &objects[0];
+
+  // The span is widened by the frames that reach into the tone and by the
+  // cells of the grid.
+  assert!((object.start - 0.5_f64).abs() < 0.1_f64, "starts at {}", object.start);
+  assert!((object.end - 1.5_f64).abs() < 0.1_f64, "ends at {}", object.end);
+
+  assert!(object.low_frequency <= 1000_f32 && object.high_frequency >= 1000_f32);
+  assert!(object.high_frequency - object.low_frequency < 500_f32, "{} to {} Hz", object.low_frequency, object.high_frequency);
+}
+
+#[test]
+fn silence_has_no_objects() {
+  let (auditory, visual) = configs();
+  let spectrogram = stft(&Signal::new(vec!(0_f32; SAMPLE_RATE as usize), SAMPLE_RATE), &auditory);
+
+  assert!(find_spectral_objects(&spectrogram, &visual).is_empty());
+}
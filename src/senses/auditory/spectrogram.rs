@@ -0,0 +1,110 @@
+use std::path::Path;
+
+use senses::auditory::image::{GrayImage, Luma};
+use senses::error::SensesError;
+
+/// Range of decibels below the loudest bin that is drawn into the image.
+/// Anything quieter is black.
+const DYNAMIC_RANGE: f32 = 80_f32;
+
+/// Whether the bins hold the amplitude of their frequency or its square.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SpectrumScale {
+  Magnitude,
+  Power,
+}
+
+/// Spectrum of the signal over time as calculated by `stft`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Spectrogram {
+
+  /// Spectra in time order. Each of them has frame_length / 2 + 1 bins going
+  /// from 0 Hz up to the Nyquist frequency.
+  pub frames: Vec<Vec<f32>>,
+
+  pub scale: SpectrumScale,
+
+  /// Sample rate of the signal the spectrogram was calculated from.
+  pub sample_rate: u32,
+
+  /// Number of samples in each frame.
+  pub frame_length: usize,
+
+  /// Number of samples between the starts of two consecutive frames.
+  pub hop: usize,
+
+}
+
+impl Spectrogram {
+
+  /// Number of frequency bins in each frame.
+  pub fn bins(&self) -> usize {
+    self.frame_length / 2 + 1
+  }
+
+  /// Centre frequency of given bin in Hz.
+  pub fn frequency(&self, bin: usize) -> f32 {
+    bin as f32 * self.sample_rate as f32 / self.frame_length as f32
+  }
+
+  /// Time of the centre of given frame in seconds.
+  pub fn time(&self, frame: usize) -> f64 {
+    (frame * self.hop + self.frame_length / 2) as f64 / self.sample_rate as f64
+  }
+
+  /// Converts a magnitude spectrogram into a power one.
+  pub fn power(&self) -> Spectrogram {
+    match self.scale {
+      SpectrumScale::Power => self.clone(),
+      SpectrumScale::Magnitude => Spectrogram {
+        frames: self.frames.iter().map(
+          |frame| frame.iter().map(|bin| bin * bin).collect()
+        ).collect(),
+        scale: SpectrumScale::Power,
+        ..self.clone()
+      },
+    }
+  }
+
+  /// Loudness of each bin in decibels relative to the loudest bin, mapped
+  /// from the bottom of the dynamic range to the loudest bin into range of 0
+  /// to 1. Frames are in time order as in `frames`.
+  pub fn brightness(&self) -> Vec<Vec<f32>> {
+    let power = self.power();
+    let loudest = power.frames.iter()
+      .flat_map(|frame| frame.iter())
+      .fold(f32::MIN_POSITIVE, |max, bin| max.max(*bin));
+
+    power.frames.iter().map(|frame| {
+      frame.iter().map(|bin| {
+        let decibels = 10_f32 * (bin.max(f32::MIN_POSITIVE) / loudest).log10();
+
+        (decibels + DYNAMIC_RANGE).max(0_f32) / DYNAMIC_RANGE
+      }).collect()
+    }).collect()
+  }
+
+  /// Draws the brightness of the spectrogram. Time goes from left to right
+  /// and frequency from the bottom up.
+  pub fn to_image(&self) -> GrayImage {
+    let brightness = self.brightness();
+
+    let width = self.frames.len().max(1) as u32;
+    let height = self.bins() as u32;
+
+    GrayImage::from_fn(width, height, |x, y| {
+      let value = brightness.get(x as usize)
+        .map_or(0_f32, |frame| frame[(height - 1 - y) as usize]);
+
+      Luma([(value * 255_f32).round() as u8])
+    })
+  }
+
+  /// Saves the image of the spectrogram, see `to_image`.
+  pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SensesError> {
+    self.to_image().save(path)?;
+
+    Ok(())
+  }
+
+}
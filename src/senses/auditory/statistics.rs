@@ -0,0 +1,263 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use senses::error::SensesError;
+use senses::auditory::signal::{zero_crossing_rate, Signal};
+
+/// Length in seconds of the windows the levels over time are measured in.
+const LEVEL_WINDOW: f64 = 0.1_f64;
+
+/// Decibels that stand for digital silence, so that every level is finite.
+const SILENCE_DB: f32 = -120_f32;
+
+/// Samples at or above this absolute value are at full scale.
+const CLIP_LEVEL: f32 = 0.999_f32;
+
+/// How many consecutive samples at full scale make the signal clipped. A
+/// single sample can reach full scale in a loud but clean recording.
+const CLIP_RUN: usize = 3;
+
+/// Share of the windows at either end of the levels that is ignored when the
+/// dynamic range is measured, so that a click or a stretch of digital silence
+/// does not decide it.
+const RANGE_PERCENTILE: f32 = 0.1_f32;
+
+/// Absolute mean of the samples above which the signal has a DC offset.
+const MAX_DC_OFFSET: f32 = 0.01_f32;
+
+/// Peak level in decibels below which the signal is considered silent.
+const MIN_PEAK_DB: f32 = -60_f32;
+
+/// Level of a single window of the signal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Level {
+
+  /// Time the window starts at in seconds.
+  pub time: f64,
+
+  /// Root mean square of the samples in decibels relative to full scale.
+  pub rms: f32,
+
+  /// Largest absolute sample in decibels relative to full scale.
+  pub peak: f32,
+
+}
+
+/// Report of the basic statistics of a signal. Besides describing the sound,
+/// it tells whether the WAV it was loaded from is fit to be learned from,
+/// see `issues`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignalStatistics {
+
+  /// Length of the signal in seconds.
+  pub duration: f64,
+
+  pub sample_rate: u32,
+
+  /// Root mean square of all samples in decibels relative to full scale.
+  pub rms: f32,
+
+  /// Largest absolute sample in decibels relative to full scale.
+  pub peak: f32,
+
+  /// Mean of the samples, 0 for a signal centred around silence.
+  pub dc_offset: f32,
+
+  /// Ratio of the peak to the root mean square in decibels. Sine has 3 dB,
+  /// heavily compressed or clipped sound has little more.
+  pub crest_factor: f32,
+
+  /// Number of samples at full scale.
+  pub clipped_samples: usize,
+
+  /// Number of runs of at least CLIP_RUN consecutive samples at full scale.
+  pub clipped_runs: usize,
+
+  /// Share of neighbouring samples that have different signs.
+  pub zero_crossing_rate: f32,
+
+  /// Difference in decibels between the loud and the quiet windows of the
+  /// signal, without the loudest and the quietest tenth of them.
+  pub dynamic_range: f32,
+
+  /// Levels of consecutive windows of LEVEL_WINDOW seconds.
+  pub levels: Vec<Level>,
+
+}
+
+impl SignalStatistics {
+
+  /// Problems of the signal that make it a poor input, empty if there are
+  /// none.
+  pub fn issues(&self) -> Vec<String> {
+    let mut issues: Vec<String> = Vec::new();
+
+    if self.clipped_runs > 0 {
+      issues.push(format!(
+        "clipped in {} places, {} samples at full scale",
+        self.clipped_runs,
+        self.clipped_samples,
+      ));
+    }
+
+    if self.dc_offset.abs() > MAX_DC_OFFSET {
+      issues.push(format!("DC offset of {}", self.dc_offset));
+    }
+
+    if self.peak < MIN_PEAK_DB {
+      issues.push(format!("silent, peaks at {} dB", self.peak));
+    }
+
+    issues
+  }
+
+  /// Formats the report as a JSON object. Values that are not finite, which
+  /// JSON cannot represent, are null.
+  pub fn to_json(&self) -> String {
+    let levels: Vec<String> = self.levels.iter().map(|level| {
+      format!(
+        "      {{ \"time\": {}, \"rms\": {}, \"peak\": {} }}",
+        json_number(level.time),
+        json_number(level.rms),
+        json_number(level.peak),
+      )
+    }).collect();
+
+    let issues: Vec<String> = self.issues().iter()
+      .map(|issue| format!("    \"{}\"", issue))
+      .collect();
+
+    let mut json: String = String::from("{\n");
+    json.push_str(&format!("  \"duration\": {},\n", json_number(self.duration)));
+    json.push_str(&format!("  \"sample_rate\": {},\n", self.sample_rate));
+    json.push_str(&format!("  \"rms\": {},\n", json_number(self.rms)));
+    json.push_str(&format!("  \"peak\": {},\n", json_number(self.peak)));
+    json.push_str(&format!("  \"dc_offset\": {},\n", json_number(self.dc_offset)));
+    json.push_str(&format!("  \"crest_factor\": {},\n", json_number(self.crest_factor)));
+    json.push_str(&format!(
+      "  \"clipping\": {{ \"samples\": {}, \"runs\": {} }},\n",
+      self.clipped_samples,
+      self.clipped_runs,
+    ));
+    json.push_str(&format!("  \"zero_crossing_rate\": {},\n", json_number(self.zero_crossing_rate)));
+    json.push_str(&format!("  \"dynamic_range\": {},\n", json_number(self.dynamic_range)));
+    json.push_str(&format!(
+      "  \"levels\": {{\n    \"window\": {},\n    \"frames\": {}\n  }},\n",
+      LEVEL_WINDOW,
+      json_array(&levels, "    "),
+    ));
+    json.push_str(&format!("  \"issues\": {}\n", json_array(&issues, "  ")));
+    json.push_str("}\n");
+
+    json
+  }
+
+  /// Writes the report as JSON, see `to_json`.
+  pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SensesError> {
+    fs::write(path, self.to_json())?;
+
+    Ok(())
+  }
+
+}
+
+/// Measures the levels, DC offset, clipping, zero-crossing rate and dynamic
+/// range of the signal. Levels are in decibels relative to full scale, where
+/// a sample of 1 is 0 dB.
+pub fn signal_statistics(signal: &Signal) -> SignalStatistics {
+  let samples = &signal.samples;
+  let count = samples.len().max(1) as f32;
+
+  let dc_offset = samples.iter().sum::<f32>() / count;
+  let rms = root_mean_square(samples);
+  let peak = samples.iter().fold(0_f32, |max, sample| max.max(sample.abs()));
+
+  // Clipping shows as runs of samples stuck at full scale.
+  let mut clipped_samples = 0;
+  let mut clipped_runs = 0;
+  let mut run = 0;
+
+  for sample in samples.iter() {
+    if sample.abs() >= CLIP_LEVEL {
+      clipped_samples += 1;
+      run += 1;
+
+      if run == CLIP_RUN {
+        clipped_runs += 1;
+      }
+    } else {
+      run = 0;
+    }
+  }
+
+  let window = ((LEVEL_WINDOW * signal.sample_rate as f64).round() as usize).max(1);
+  let levels: Vec<Level> = samples.chunks(window).enumerate().map(|(index, chunk)| {
+    Level {
+      time: (index * window) as f64 / signal.sample_rate as f64,
+      rms: decibels(root_mean_square(chunk)),
+      peak: decibels(chunk.iter().fold(0_f32, |max, sample| max.max(sample.abs()))),
+    }
+  }).collect();
+
+  let mut loudness: Vec<f32> = levels.iter().map(|level| level.rms).collect();
+  loudness.sort_by(|a, b| a.total_cmp(b));
+
+  let dynamic_range = if loudness.is_empty() {
+    0_f32
+  } else {
+    let skipped = (loudness.len() as f32 * RANGE_PERCENTILE) as usize;
+
+    loudness[loudness.len() - 1 - skipped] - loudness[skipped]
+  };
+
+  SignalStatistics {
+    duration: signal.duration(),
+    sample_rate: signal.sample_rate,
+    rms: decibels(rms),
+    peak: decibels(peak),
+    dc_offset,
+    crest_factor: if rms > 0_f32 { decibels(peak / rms) } else { 0_f32 },
+    clipped_samples,
+    clipped_runs,
+    zero_crossing_rate: zero_crossing_rate(samples),
+    dynamic_range,
+    levels,
+  }
+}
+
+/// Formats already formatted and indented items as a JSON array whose closing
+/// bracket has given indentation.
+fn json_array(items: &[String], indentation: &str) -> String {
+  if items.is_empty() {
+    "[]".to_string()
+  } else {
+    format!("[\n{}\n{}]", items.join(",\n"), indentation)
+  }
+}
+
+/// Formats a number as JSON, where there is no infinity nor NaN.
+fn json_number<T: fmt::Display + Into<f64> + Copy>(value: T) -> String {
+  if value.into().is_finite() {
+    value.to_string()
+  } else {
+    "null".to_string()
+  }
+}
+
+fn root_mean_square(samples: &[f32]) -> f32 {
+  if samples.is_empty() {
+    return 0_f32;
+  }
+
+  (samples.iter().map(|sample| sample * sample).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+/// Amplitude in decibels relative to full scale.
+fn decibels(amplitude: f32) -> f32 {
+  if amplitude > 0_f32 {
+    (20_f32 * amplitude.log10()).max(SILENCE_DB)
+  } else {
+    SILENCE_DB
+  }
+}
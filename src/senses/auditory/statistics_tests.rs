@@ -0,0 +1,104 @@
+//! Tests of the statistics report on signals whose levels are known.
+
+use std::f32::consts::PI;
+
+use senses::auditory::signal::{zero_crossing_rate, Signal};
+use senses::auditory::statistics::signal_statistics;
+
+const SAMPLE_RATE: u32 = 8000;
+
+/// Second of a sine of given amplitude, shifted by the offset. The frequency
+/// divides the sample rate, so that the second holds whole periods.
+fn sine(amplitude: f32, offset: f32) -> Signal {
+  let samples = (0..SAMPLE_RATE)
+    .map(|n| offset + amplitude * (2_f32 * PI * 100_f32 * n as f32 / SAMPLE_RATE as f32).sin())
+    .collect();
+
+  Signal::new(samples, SAMPLE_RATE)
+}
+
+#[test]
+fn only_runs_of_samples_at_full_scale_are_clipping() {
+  let samples = vec!(
+    0_f32, 1_f32, 1_f32, 1_f32, 0.5_f32,
+    1_f32, 1_f32, 0_f32,
+    -1_f32, -1_f32, -1_f32, -1_f32, 0_f32,
+    0.9995_f32, 1_f32, -1_f32,
+  );
+  let statistics = signal_statistics(&Signal::new(samples, SAMPLE_RATE));
+
+  assert_eq!(statistics.clipped_samples, 12);
+  assert_eq!(statistics.clipped_runs, 3);
+  assert!(statistics.issues()[0].starts_with("clipped in 3 places"));
+}
+
+#[test]
+fn sine_peaks_3_db_above_its_rms() {
+  let statistics = signal_statistics(&sine(0.5_f32, 0_f32));
+
+  assert!((statistics.crest_factor - 3.01_f32).abs() < 0.01_f32, "{}", statistics.crest_factor);
+  assert!((statistics.peak + 6.02_f32).abs() < 0.01_f32, "{}", statistics.peak);
+  assert!((statistics.rms + 9.03_f32).abs() < 0.01_f32, "{}", statistics.rms);
+  assert_eq!(statistics.clipped_samples, 0);
+
+  // Levels of a steady sine do not change over time.
+  assert_eq!(statistics.levels.len(), 10);
+  assert!(statistics.dynamic_range.abs() < 0.01_f32);
+  assert!(statistics.issues().is_empty(), "{:?}", statistics.issues());
+}
+
+#[test]
+fn mean_of_the_samples_is_the_dc_offset() {
+  let statistics = signal_statistics(&sine(0.5_f32, 0.1_f32));
+
+  assert!((statistics.dc_offset - 0.1_f32).abs() < 1e-4_f32, "{}", statistics.dc_offset);
+  assert_eq!(statistics.issues().len(), 1);
+  assert!(statistics.issues()[0].starts_with("DC offset"));
+
+  assert!(signal_statistics(&sine(0.5_f32, 0_f32)).dc_offset.abs() < 1e-4_f32);
+}
+
+#[test]
+fn zero_crossings_are_counted_between_neighbours() {
+  assert_eq!(zero_crossing_rate(&[]), 0_f32);
+  assert_eq!(zero_crossing_rate(&[1_f32, -1_f32, 1_f32, -1_f32, 1_f32]), 1_f32);
+  assert_eq!(zero_crossing_rate(&[1_f32, 2_f32, -1_f32, -2_f32, 0_f32]), 0.5_f32);
+}
+
+#[test]
+fn json_has_every_field_and_no_bare_non_finite_numbers() {
+  let mut statistics = signal_statistics(&sine(0.5_f32, 0_f32));
+  let json = statistics.to_json();
+
+  let keys = [
+    "duration", "sample_rate", "rms", "peak", "dc_offset", "crest_factor", "clipping",
+    "zero_crossing_rate", "dynamic_range", "levels", "issues",
+  ];
+  let lines: Vec<&str> = json.lines().collect();
+
+  assert_eq!(lines[0], "{");
+  assert_eq!(*lines.last().unwrap(), "}");
+
+  // Fields of the report are the lines indented by one level.
+  let fields: Vec<&str> = lines.iter().filter(|line| line.starts_with("  \"")).cloned().collect();
+  assert_eq!(fields.len(), keys.len());
+  for (line, key) in fields.iter().zip(keys.iter()) {
+    assert!(line.starts_with(&format!("  \"{}\": ", key)), "{} is not {}", line, key);
+  }
+  assert_eq!(json.matches("\"time\"").count(), statistics.levels.len());
+  assert!(json.contains("\"issues\": []"));
+  assert_eq!(json.matches('{').count(), json.matches('}').count());
+  assert_eq!(json.matches('[').count(), json.matches(']').count());
+
+  statistics.dc_offset = f32::NAN;
+  statistics.crest_factor = f32::INFINITY;
+  statistics.levels[0].rms = f32::NEG_INFINITY;
+  let json = statistics.to_json();
+
+  assert!(json.contains("\"dc_offset\": null,"));
+  assert!(json.contains("\"crest_factor\": null,"));
+  assert!(json.contains("\"rms\": null, \"peak\""));
+  for line in json.lines().filter(|line| !line.starts_with("    \"")) {
+    assert!(!line.contains("NaN") && !line.contains("inf"), "{}", line);
+  }
+}
@@ -0,0 +1,51 @@
+use senses::auditory::config::AuditoryConfig;
+use senses::auditory::rustfft::FftPlanner;
+use senses::auditory::rustfft::num_complex::Complex;
+use senses::auditory::signal::Signal;
+use senses::auditory::spectrogram::{Spectrogram, SpectrumScale};
+
+/// Calculates the magnitude spectrogram of the signal with the short-time
+/// Fourier transform. The signal is cut into frames of given length every hop
+/// samples, each frame is multiplied by the window and transformed. The end
+/// of the signal is padded with silence so that every sample is in a frame.
+///
+/// Magnitudes are divided by half of the sum of the window, so that a sine of
+/// amplitude 1 peaks at roughly 1 whatever the window and frame length.
+pub fn stft(signal: &Signal, config: &AuditoryConfig) -> Spectrogram {
+  let (frame_length, hop) = (config.frame_length, config.hop);
+  let window = config.window.weights(frame_length);
+  let scale = 2_f32 / window.iter().sum::<f32>();
+
+  let samples = &signal.samples;
+  let count = if samples.len() <= frame_length {
+    1
+  } else {
+    1 + (samples.len() - frame_length).div_ceil(hop)
+  };
+
+  let fft = FftPlanner::new().plan_fft_forward(frame_length);
+  let mut buffer: Vec<Complex<f32>> = vec!(Complex::new(0_f32, 0_f32); frame_length);
+
+  let frames: Vec<Vec<f32>> = (0..count).map(|frame| {
+    let start = frame * hop;
+
+    for (n, value) in buffer.iter_mut().enumerate() {
+      let sample = samples.get(start + n).cloned().unwrap_or(0_f32);
+      *value = Complex::new(sample * window[n], 0_f32);
+    }
+
+    fft.process(&mut buffer);
+
+    // Spectrum of a real signal is symmetric, only the bins up to the Nyquist
+    // frequency are kept.
+    buffer[..(frame_length / 2 + 1)].iter().map(|bin| bin.norm() * scale).collect()
+  }).collect();
+
+  Spectrogram {
+    frames,
+    scale: SpectrumScale::Magnitude,
+    sample_rate: signal.sample_rate,
+    frame_length,
+    hop,
+  }
+}
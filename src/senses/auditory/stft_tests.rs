@@ -0,0 +1,75 @@
+//! Tests of the short-time Fourier transform on unit sines whose frequency is
+//! the centre of a bin, so that the whole sine falls into that bin.
+
+use std::f32::consts::PI;
+
+use senses::auditory::config::{AuditoryConfig, Window};
+use senses::auditory::signal::Signal;
+use senses::auditory::stft::stft;
+
+const SAMPLE_RATE: u32 = 44100;
+
+/// Sine of amplitude one at the centre frequency of given bin.
+fn sine(bin: usize, length: usize, config: &AuditoryConfig) -> Signal {
+  let frequency = bin as f32 * SAMPLE_RATE as f32 / config.frame_length as f32;
+  let samples = (0..length)
+    .map(|n| (2_f32 * PI * frequency * n as f32 / SAMPLE_RATE as f32).sin())
+    .collect();
+
+  Signal::new(samples, SAMPLE_RATE)
+}
+
+#[test]
+fn unit_sine_peaks_at_one_in_its_bin() {
+  for window in [Window::Hann, Window::Hamming].iter() {
+    let config = AuditoryConfig { window: *window, ..AuditoryConfig::default() };
+    let spectrogram = stft(&sine(100, 8 * config.frame_length, &config), &config);
+
+    // Frames that reach past the end of the signal are padded with silence.
+    let whole = 1 + (7 * config.frame_length) / config.hop;
+
+    for frame in spectrogram.frames[..whole].iter() {
+      let (peak, magnitude) = frame.iter().enumerate()
+        .fold((0, 0_f32), |best, (bin, value)| if *value > best.1 { (bin, *value) } else { best });
+
+      assert_eq!(peak, 100, "{:?}", window);
+      assert!((magnitude - 1_f32).abs() < 0.01_f32, "{:?} peaks at {}", window, magnitude);
+    }
+
+    assert!((spectrogram.frequency(100) - 2153.3_f32).abs() < 0.1_f32);
+  }
+}
+
+#[test]
+fn frames_cover_every_sample() {
+  let config = AuditoryConfig::default();
+  let count = |length: usize| stft(&sine(10, length, &config), &config).frames.len();
+
+  // Short signals are padded into a single frame.
+  assert_eq!(count(0), 1);
+  assert_eq!(count(config.frame_length), 1);
+
+  // Each hop started past the first frame adds a frame, a partial one too.
+  assert_eq!(count(config.frame_length + 1), 2);
+  assert_eq!(count(config.frame_length + config.hop), 2);
+  assert_eq!(count(config.frame_length + config.hop + 1), 3);
+  assert_eq!(count(config.frame_length + 10 * config.hop), 11);
+}
+
+#[test]
+fn image_has_low_frequencies_at_the_bottom() {
+  let config = AuditoryConfig { frame_length: 256, hop: 128, ..AuditoryConfig::default() };
+  let spectrogram = stft(&sine(20, 10 * config.frame_length, &config), &config);
+  let image = spectrogram.to_image();
+
+  assert_eq!(image.dimensions(), (spectrogram.frames.len() as u32, spectrogram.bins() as u32));
+
+  // The loudest bin is white and drawn from the bottom up.
+  let (width, height) = image.dimensions();
+  for x in 0..width {
+    let brightest = (0..height).max_by_key(|y| image.get_pixel(x, *y).data[0]).unwrap();
+
+    assert_eq!(brightest, height - 1 - 20, "column {}", x);
+    assert_eq!(image.get_pixel(x, brightest).data[0], 255);
+  }
+}
@@ -0,0 +1,106 @@
+//! Tests of the resampler on synthetic linear sine sweeps. A sweep covers a
+//! whole band of frequencies, therefore it shows both whether the passband is
+//! kept and whether anything above the new Nyquist frequency folds back.
+
+use std::f64::consts::PI;
+
+use senses::auditory::resample::resample;
+use senses::auditory::signal::Signal;
+
+/// Length of the sweeps in seconds.
+const DURATION: f64 = 1_f64;
+
+/// Samples at either end that are skipped, the filter only sees half of its
+/// taps there.
+const EDGE: usize = 200;
+
+/// Sine sweeping linearly from one frequency to the other with amplitude of
+/// one half.
+fn sweep(from: f64, to: f64, sample_rate: u32) -> Signal {
+  let length = (DURATION * sample_rate as f64) as usize;
+
+  let samples = (0..length).map(|n| {
+    let time = n as f64 / sample_rate as f64;
+    let phase = 2_f64 * PI * (from * time + (to - from) * time * time / (2_f64 * DURATION));
+
+    (0.5_f64 * phase.sin()) as f32
+  }).collect();
+
+  Signal::new(samples, sample_rate)
+}
+
+fn rms(samples: &[f32]) -> f64 {
+  let total: f64 = samples.iter().map(|sample| (*sample as f64).powi(2)).sum();
+
+  (total / samples.len() as f64).sqrt()
+}
+
+/// Power of the difference between the signals relative to the power of the
+/// expected one, in decibels.
+fn error_db(found: &Signal, expected: &Signal) -> f64 {
+  let length = found.samples.len().min(expected.samples.len()) - EDGE;
+  let difference: Vec<f32> = (EDGE..length)
+    .map(|n| found.samples[n] - expected.samples[n])
+    .collect();
+
+  20_f64 * (rms(&difference) / rms(&expected.samples[EDGE..length])).log10()
+}
+
+#[test]
+fn same_rate_keeps_signal() {
+  let signal = sweep(100_f64, 1000_f64, 16000);
+
+  assert_eq!(resample(&signal, 16000), signal);
+}
+
+#[test]
+fn length_follows_rate() {
+  let signal = sweep(100_f64, 1000_f64, 44100);
+
+  assert_eq!(resample(&signal, 48000).samples.len(), 48000);
+  assert_eq!(resample(&signal, 16000).samples.len(), 16000);
+  assert_eq!(resample(&signal, 22050).samples.len(), 22050);
+}
+
+#[test]
+fn downsampling_keeps_passband() {
+  let found = resample(&sweep(50_f64, 6000_f64, 48000), 16000);
+  let expected = sweep(50_f64, 6000_f64, 16000);
+
+  let error = error_db(&found, &expected);
+  assert!(error < -60_f64, "passband error is {:.1} dB", error);
+}
+
+#[test]
+fn downsampling_removes_frequencies_above_nyquist() {
+  // Everything in the sweep is above 8 kHz, the Nyquist frequency of 16 kHz.
+  // Without the low pass, it would fold back into the audible band at full
+  // strength.
+  let input = sweep(8500_f64, 23000_f64, 48000);
+  let found = resample(&input, 16000);
+
+  let length = found.samples.len() - EDGE;
+  let attenuation = 20_f64 * (rms(&found.samples[EDGE..length]) / rms(&input.samples)).log10();
+
+  assert!(attenuation < -80_f64, "aliases are attenuated by {:.1} dB only", attenuation);
+}
+
+#[test]
+fn upsampling_matches_sweep_without_images() {
+  // Spectral images of the sweep above 8 kHz would show up as an error
+  // against the sweep generated at the higher rate directly.
+  let found = resample(&sweep(50_f64, 7000_f64, 16000), 44100);
+  let expected = sweep(50_f64, 7000_f64, 44100);
+
+  let error = error_db(&found, &expected);
+  assert!(error < -50_f64, "upsampling error is {:.1} dB", error);
+}
+
+#[test]
+fn odd_ratio_keeps_passband() {
+  let found = resample(&sweep(50_f64, 15000_f64, 44100), 48000);
+  let expected = sweep(50_f64, 15000_f64, 48000);
+
+  let error = error_db(&found, &expected);
+  assert!(error < -60_f64, "44.1 to 48 kHz error is {:.1} dB", error);
+}
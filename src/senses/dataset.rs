@@ -0,0 +1,232 @@
+extern crate hound;
+
+use std::fs;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use senses::error::SensesError;
+use senses::media_source::{MediaKind, MediaSource, TimeRange};
+
+/// Directory with clips laid out as `<root>/<clip>/video` with frames and
+/// `<root>/<clip>/audio` with the WAV.
+pub struct Dataset {
+  root: PathBuf,
+}
+
+impl Dataset {
+
+  /// Opens the dataset in given directory.
+  pub fn open<P: Into<PathBuf>>(root: P) -> Result<Dataset, SensesError> {
+    let root: PathBuf = root.into();
+
+    // Fails with an I/O error if the root does not exist.
+    fs::read_dir(&root)?;
+
+    Ok(Dataset { root })
+  }
+
+  pub fn root(&self) -> &Path {
+    &self.root
+  }
+
+  /// Lists all clips in the dataset ordered by their identifier. Every
+  /// directory in the root is a clip.
+  pub fn clips(&self) -> Result<Vec<Clip>, SensesError> {
+    let mut clips: Vec<Clip> = Vec::new();
+
+    for entry in fs::read_dir(&self.root)? {
+      let path = entry?.path();
+
+      if path.is_dir() {
+        clips.push(Clip::open(path)?);
+      }
+    }
+
+    clips.sort_by(|a, b| a.id.cmp(&b.id));
+
+    Ok(clips)
+  }
+
+  /// Opens a single clip of the dataset.
+  pub fn clip(&self, id: &str) -> Result<Clip, SensesError> {
+    Clip::open(self.root.join(id))
+  }
+
+}
+
+/// Problem with a clip. Most of them make the clip unusable for the model,
+/// warnings only point out audio that has to be converted before it is used.
+#[derive(Debug, PartialEq)]
+pub enum ClipIssue {
+
+  /// There are no frames in the `video` directory of the clip.
+  MissingFrames,
+
+  /// There is no WAV in the `audio` directory of the clip.
+  MissingAudio,
+
+  /// There is more than one WAV in the `audio` directory of the clip.
+  AmbiguousAudio(usize),
+
+  /// WAV of the clip could not be read.
+  MalformedAudio(String),
+
+  /// Audio has given number of channels instead of one. This is a warning,
+  /// the channels are averaged into mono when the WAV is loaded.
+  NotMono(u16),
+
+  /// Audio is sampled at the first rate instead of the expected second one.
+  /// This is a warning, loading keeps the rate of the WAV and the loaded
+  /// signal has to be resampled to the expected one.
+  WrongSampleRate(u32, u32),
+
+}
+
+impl ClipIssue {
+
+  /// Whether the clip can still be used despite the issue.
+  pub fn is_warning(&self) -> bool {
+    matches!(self, ClipIssue::NotMono(_) | ClipIssue::WrongSampleRate(_, _))
+  }
+
+}
+
+impl fmt::Display for ClipIssue {
+
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ClipIssue::MissingFrames => write!(formatter, "no frames in the video directory"),
+      ClipIssue::MissingAudio => write!(formatter, "no WAV in the audio directory"),
+      ClipIssue::AmbiguousAudio(count) => write!(formatter, "{} WAVs in the audio directory", count),
+      ClipIssue::MalformedAudio(error) => write!(formatter, "WAV cannot be read: {}", error),
+      ClipIssue::NotMono(channels) => write!(formatter, "WAV has {} channels instead of 1", channels),
+      ClipIssue::WrongSampleRate(rate, expected) => write!(
+        formatter,
+        "WAV is sampled at {} Hz instead of {} Hz",
+        rate,
+        expected,
+      ),
+    }
+  }
+
+}
+
+/// Handle of a single clip of the dataset, which gives the media sources of
+/// its frames and audio to the senses.
+#[derive(Clone, Debug)]
+pub struct Clip {
+
+  /// Name of the clip directory.
+  id: String,
+
+  root: PathBuf,
+
+  /// Frames in the `video` directory ordered by their file names.
+  frames: Vec<PathBuf>,
+
+  /// WAVs in the `audio` directory ordered by their file names.
+  audio: Vec<PathBuf>,
+
+}
+
+impl Clip {
+
+  /// Scans the clip directory for frames and audio. Missing `video` or
+  /// `audio` directories are not an error, they are reported by `validate`.
+  pub fn open<P: Into<PathBuf>>(root: P) -> Result<Clip, SensesError> {
+    let root: PathBuf = root.into();
+    let id = root.file_name()
+      .map(|name| name.to_string_lossy().into_owned())
+      .unwrap_or_default();
+
+    let frames = list_media(&root.join("video"), MediaKind::Frame)?;
+    let audio = list_media(&root.join("audio"), MediaKind::Wav)?;
+
+    Ok(Clip { id, root, frames, audio })
+  }
+
+  pub fn id(&self) -> &str {
+    &self.id
+  }
+
+  /// All frames of the clip as a single sequence.
+  pub fn video(&self) -> MediaSource {
+    MediaSource::new(self.root.join("video"), MediaKind::FrameSequence)
+      .with_clip(self.id.clone())
+  }
+
+  /// Frames of the clip, each with the part of the clip it stands for given
+  /// the frame rate they were extracted at.
+  pub fn frames(&self, fps: u32) -> Vec<MediaSource> {
+    self.frames.iter().enumerate().map(|(index, path)| {
+      let start = index as f64 / fps as f64;
+
+      MediaSource::new(path.clone(), MediaKind::Frame)
+        .with_clip(self.id.clone())
+        .with_time_range(TimeRange::new(start, start + 1_f64 / fps as f64))
+    }).collect()
+  }
+
+  /// Audio of the clip if there is exactly one WAV.
+  pub fn audio(&self) -> Option<MediaSource> {
+    if self.audio.len() != 1 {
+      return None;
+    }
+
+    Some(MediaSource::new(self.audio[0].clone(), MediaKind::Wav).with_clip(self.id.clone()))
+  }
+
+  /// Checks that the clip has frames and a single readable WAV. A WAV that is
+  /// not mono or not sampled at given rate is only warned about, it is averaged
+  /// into mono when it is loaded and can be resampled afterwards.
+  pub fn validate(&self, sample_rate: u32) -> Vec<ClipIssue> {
+    let mut issues: Vec<ClipIssue> = Vec::new();
+
+    if self.frames.is_empty() {
+      issues.push(ClipIssue::MissingFrames);
+    }
+
+    match self.audio.len() {
+      0 => issues.push(ClipIssue::MissingAudio),
+      1 => match hound::WavReader::open(&self.audio[0]) {
+        Ok(reader) => {
+          let spec = reader.spec();
+
+          if spec.channels != 1 {
+            issues.push(ClipIssue::NotMono(spec.channels));
+          }
+
+          if spec.sample_rate != sample_rate {
+            issues.push(ClipIssue::WrongSampleRate(spec.sample_rate, sample_rate));
+          }
+        },
+        Err(error) => issues.push(ClipIssue::MalformedAudio(error.to_string())),
+      },
+      count => issues.push(ClipIssue::AmbiguousAudio(count)),
+    }
+
+    issues
+  }
+
+}
+
+/// Lists files of given kind in a directory ordered by their names. Missing
+/// directory has no files.
+fn list_media(directory: &Path, kind: MediaKind) -> Result<Vec<PathBuf>, SensesError> {
+  if !directory.is_dir() {
+    return Ok(Vec::new());
+  }
+
+  let mut paths: Vec<PathBuf> = Vec::new();
+  for entry in fs::read_dir(directory)? {
+    let path = entry?.path();
+
+    if MediaSource::from_path(&path).map(|source| source.kind() == kind).unwrap_or(false) {
+      paths.push(path);
+    }
+  }
+
+  paths.sort();
+
+  Ok(paths)
+}
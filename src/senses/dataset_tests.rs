@@ -0,0 +1,147 @@
+//! Tests of scanning and validating clips of a dataset laid out in a
+//! temporary directory.
+
+extern crate hound;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use senses::dataset::{Clip, ClipIssue, Dataset};
+use senses::media_source::TimeRange;
+
+use self::hound::{SampleFormat, WavSpec, WavWriter};
+
+const SAMPLE_RATE: u32 = 16000;
+
+/// Empty dataset root of its own.
+fn root(name: &str) -> PathBuf {
+  let root = env::temp_dir().join(format!("dataset-{}-{}", name, process::id()));
+  let _ = fs::remove_dir_all(&root);
+  fs::create_dir_all(&root).unwrap();
+
+  root
+}
+
+/// Creates a clip with given number of frames, the frames are empty files as
+/// only their names matter.
+fn clip(root: &Path, id: &str, frames: usize) -> PathBuf {
+  let clip = root.join(id);
+  fs::create_dir_all(clip.join("video")).unwrap();
+  fs::create_dir_all(clip.join("audio")).unwrap();
+
+  for index in 0..frames {
+    fs::write(clip.join("video").join(format!("output_{:04}.png", index + 1)), b"").unwrap();
+  }
+
+  clip
+}
+
+/// Writes a short silent WAV.
+fn wav(path: &Path, channels: u16, sample_rate: u32) {
+  let spec = WavSpec { channels, sample_rate, bits_per_sample: 16, sample_format: SampleFormat::Int };
+  let mut writer = WavWriter::create(path, spec).unwrap();
+
+  for _ in 0..(100 * channels) {
+    writer.write_sample(0_i16).unwrap();
+  }
+  writer.finalize().unwrap();
+}
+
+#[test]
+fn clips_are_ordered_by_their_ids() {
+  let root = root("order");
+  for id in ["clip-b", "clip-c", "clip-a"].iter() {
+    clip(&root, id, 1);
+  }
+  fs::write(root.join("readme.txt"), b"").unwrap();
+
+  let dataset = Dataset::open(&root).unwrap();
+  let ids: Vec<String> = dataset.clips().unwrap().iter().map(|clip| clip.id().to_string()).collect();
+  assert_eq!(ids, vec!("clip-a", "clip-b", "clip-c"));
+
+  assert_eq!(dataset.clip("clip-b").unwrap().id(), "clip-b");
+  assert!(Dataset::open(root.join("missing")).is_err());
+
+  fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn clip_without_directories_is_missing_everything() {
+  let root = root("empty");
+  fs::create_dir_all(root.join("bare")).unwrap();
+
+  let clip = Clip::open(root.join("bare")).unwrap();
+  assert!(clip.frames(25).is_empty());
+  assert!(clip.audio().is_none());
+  assert_eq!(clip.validate(SAMPLE_RATE), vec!(ClipIssue::MissingFrames, ClipIssue::MissingAudio));
+
+  fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn clip_needs_exactly_one_readable_wav() {
+  let root = root("audio");
+
+  let path = clip(&root, "none", 1);
+  assert_eq!(Clip::open(&path).unwrap().validate(SAMPLE_RATE), vec!(ClipIssue::MissingAudio));
+
+  let path = clip(&root, "two", 1);
+  wav(&path.join("audio").join("a.wav"), 1, SAMPLE_RATE);
+  wav(&path.join("audio").join("b.wav"), 1, SAMPLE_RATE);
+  let two = Clip::open(&path).unwrap();
+  assert_eq!(two.validate(SAMPLE_RATE), vec!(ClipIssue::AmbiguousAudio(2)));
+  assert!(two.audio().is_none());
+
+  let path = clip(&root, "malformed", 1);
+  fs::write(path.join("audio").join("sound.wav"), b"not a wav").unwrap();
+  let issues = Clip::open(&path).unwrap().validate(SAMPLE_RATE);
+  assert_eq!(issues.len(), 1);
+  assert!(matches!(issues[0], ClipIssue::MalformedAudio(_)), "{:?}", issues);
+  assert!(!issues[0].is_warning());
+
+  let path = clip(&root, "valid", 1);
+  wav(&path.join("audio").join("sound.wav"), 1, SAMPLE_RATE);
+  let valid = Clip::open(&path).unwrap();
+  assert!(valid.validate(SAMPLE_RATE).is_empty());
+  assert_eq!(valid.audio().unwrap().clip(), Some("valid"));
+
+  fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn stereo_and_other_rates_are_only_warnings() {
+  let root = root("warnings");
+  let path = clip(&root, "stereo", 1);
+  wav(&path.join("audio").join("sound.wav"), 2, 44100);
+
+  let issues = Clip::open(&path).unwrap().validate(SAMPLE_RATE);
+  assert_eq!(issues, vec!(ClipIssue::NotMono(2), ClipIssue::WrongSampleRate(44100, SAMPLE_RATE)));
+  assert!(issues.iter().all(|issue| issue.is_warning()));
+
+  assert!(!ClipIssue::MissingFrames.is_warning());
+  assert!(!ClipIssue::MissingAudio.is_warning());
+  assert!(!ClipIssue::AmbiguousAudio(2).is_warning());
+
+  fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn frames_cover_consecutive_parts_of_the_clip() {
+  let root = root("frames");
+  let path = clip(&root, "clip", 3);
+
+  let frames = Clip::open(&path).unwrap().frames(4);
+  let ranges: Vec<TimeRange> = frames.iter().map(|frame| frame.time_range().unwrap()).collect();
+
+  assert_eq!(ranges, vec!(
+    TimeRange::new(0_f64, 0.25_f64),
+    TimeRange::new(0.25_f64, 0.5_f64),
+    TimeRange::new(0.5_f64, 0.75_f64),
+  ));
+  assert_eq!(frames[2].path(), path.join("video").join("output_0003.png").as_path());
+  assert!(frames.iter().all(|frame| frame.clip() == Some("clip")));
+
+  fs::remove_dir_all(&root).unwrap();
+}
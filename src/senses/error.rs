@@ -0,0 +1,100 @@
+extern crate hound;
+extern crate image;
+
+use std::io;
+use std::fmt;
+use std::error::Error;
+use std::path::PathBuf;
+
+use self::image::ImageError;
+
+/// Failures of loading, processing or saving the sensory data. None of them
+/// should stop a batch of inputs, the input is skipped instead.
+#[derive(Debug)]
+pub enum SensesError {
+
+  /// Reading or writing a file failed.
+  Io(io::Error),
+
+  /// File could not be decoded as an image.
+  ImageDecode(ImageError),
+
+  /// File could not be decoded as a WAV.
+  WavDecode(hound::Error),
+
+  /// Image cannot be split into cells of given size. Both dimensions have to
+  /// be divisible by the cell size.
+  InvalidDimensions { width: u32, height: u32, cell_size: u32 },
+
+  /// Media is of a kind that given sense cannot load.
+  UnsupportedMedia(PathBuf),
+
+}
+
+impl fmt::Display for SensesError {
+
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      SensesError::Io(error) => write!(formatter, "I/O error: {}", error),
+      SensesError::ImageDecode(error) => write!(formatter, "Could not decode image: {}", error),
+      SensesError::WavDecode(error) => write!(formatter, "Could not decode WAV: {}", error),
+      SensesError::InvalidDimensions { width, height, cell_size } => write!(
+        formatter,
+        "Image of {}x{} pixels cannot be split into cells of {} pixels",
+        width,
+        height,
+        cell_size,
+      ),
+      SensesError::UnsupportedMedia(path) => write!(
+        formatter,
+        "Media {} is not supported",
+        path.display(),
+      ),
+    }
+  }
+
+}
+
+impl Error for SensesError {
+
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    match self {
+      SensesError::Io(error) => Some(error),
+      SensesError::ImageDecode(error) => Some(error),
+      SensesError::WavDecode(error) => Some(error),
+      SensesError::InvalidDimensions { .. } => None,
+      SensesError::UnsupportedMedia(_) => None,
+    }
+  }
+
+}
+
+impl From<io::Error> for SensesError {
+
+  fn from(error: io::Error) -> SensesError {
+    SensesError::Io(error)
+  }
+
+}
+
+impl From<ImageError> for SensesError {
+
+  fn from(error: ImageError) -> SensesError {
+    match error {
+      ImageError::IoError(error) => SensesError::Io(error),
+      error => SensesError::ImageDecode(error),
+    }
+  }
+
+}
+
+impl From<hound::Error> for SensesError {
+
+  fn from(error: hound::Error) -> SensesError {
+    match error {
+      hound::Error::IoError(error) => SensesError::Io(error),
+      error => SensesError::WavDecode(error),
+    }
+  }
+
+}
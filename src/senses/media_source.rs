@@ -0,0 +1,173 @@
+use std::fs;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use senses::error::SensesError;
+
+/// Extensions of files that are loaded as single video frames.
+const FRAME_EXTENSIONS: [&str; 4] = ["png", "jpg", "jpeg", "bmp"];
+
+/// Extensions of files that are loaded as videos.
+const VIDEO_EXTENSIONS: [&str; 5] = ["avi", "mp4", "mkv", "mov", "webm"];
+
+/// What kind of media the source points to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MediaKind {
+
+  /// Single image extracted from a video.
+  Frame,
+
+  /// Directory of frames extracted from a video, ordered by their file names.
+  FrameSequence,
+
+  /// Audio track of a video in WAV format.
+  Wav,
+
+  /// Video file that has not been split into frames and audio yet.
+  Video,
+
+}
+
+/// Part of a clip given by its start and end in seconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimeRange {
+  pub start: f64,
+  pub end: f64,
+}
+
+impl TimeRange {
+
+  pub fn new(start: f64, end: f64) -> TimeRange {
+    TimeRange { start, end }
+  }
+}
+
+/// Media that the senses load their input from. Besides the path it knows
+/// what kind of media it is, to which clip it belongs and which part of the
+/// clip it covers.
+#[derive(Clone, Debug)]
+pub struct MediaSource {
+
+  path: PathBuf,
+
+  kind: MediaKind,
+
+  /// Identifier of the clip the media belongs to.
+  clip: Option<String>,
+
+  /// Part of the clip the media covers.
+  time_range: Option<TimeRange>,
+
+}
+
+impl MediaSource {
+
+  /// Factory method for media of known kind.
+  pub fn new<P: Into<PathBuf>>(path: P, kind: MediaKind) -> MediaSource {
+    MediaSource {
+      path: path.into(),
+      kind,
+      clip: None,
+      time_range: None,
+    }
+  }
+
+  /// Recognizes the kind of the media from the path. Directories are frame
+  /// sequences and files are recognized by their extension. Clip is the name
+  /// of the directory that holds the `video` or `audio` directory of the
+  /// media, which is the layout of the data directory.
+  pub fn from_path<P: Into<PathBuf>>(path: P) -> Result<MediaSource, SensesError> {
+    let path: PathBuf = path.into();
+
+    let kind = if path.is_dir() {
+      MediaKind::FrameSequence
+    } else {
+      let extension = path.extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_lowercase());
+
+      match extension {
+        Some(ref extension) if FRAME_EXTENSIONS.contains(&extension.as_str()) => MediaKind::Frame,
+        Some(ref extension) if VIDEO_EXTENSIONS.contains(&extension.as_str()) => MediaKind::Video,
+        Some(ref extension) if extension == "wav" => MediaKind::Wav,
+        _ => return Err(SensesError::UnsupportedMedia(path)),
+      }
+    };
+
+    let clip = path.ancestors()
+      .skip_while(|ancestor| {
+        let name = ancestor.file_name().and_then(|name| name.to_str());
+
+        name != Some("video") && name != Some("audio")
+      })
+      .nth(1)
+      .and_then(|clip| clip.file_name())
+      .map(|clip| clip.to_string_lossy().into_owned());
+
+    Ok(MediaSource {
+      clip,
+      ..MediaSource::new(path, kind)
+    })
+  }
+
+  /// Assigns the media to a clip.
+  pub fn with_clip<S: Into<String>>(mut self, clip: S) -> MediaSource {
+    self.clip = Some(clip.into());
+    self
+  }
+
+  /// Sets the part of the clip the media covers.
+  pub fn with_time_range(mut self, time_range: TimeRange) -> MediaSource {
+    self.time_range = Some(time_range);
+    self
+  }
+
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  pub fn kind(&self) -> MediaKind {
+    self.kind
+  }
+
+  pub fn clip(&self) -> Option<&str> {
+    self.clip.as_deref()
+  }
+
+  pub fn time_range(&self) -> Option<TimeRange> {
+    self.time_range
+  }
+
+  /// Lists frames of a frame sequence ordered by their file names. Files that
+  /// are not frames are ignored. Single frame lists itself.
+  pub fn frames(&self) -> Result<Vec<MediaSource>, SensesError> {
+    match self.kind {
+      MediaKind::Frame => Ok(vec!(self.clone())),
+      MediaKind::FrameSequence => {
+        let mut paths: Vec<PathBuf> = Vec::new();
+
+        for entry in fs::read_dir(&self.path)? {
+          paths.push(entry?.path());
+        }
+
+        paths.sort();
+
+        Ok(paths.into_iter()
+          .filter_map(|path| MediaSource::from_path(path).ok())
+          .filter(|frame| frame.kind == MediaKind::Frame)
+          .map(|frame| MediaSource { clip: self.clip.clone(), ..frame })
+          .collect())
+      },
+      _ => Err(SensesError::UnsupportedMedia(self.path.clone())),
+    }
+  }
+
+}
+
+impl fmt::Display for MediaSource {
+
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    write!(formatter, "{}", self.path.display())
+  }
+
+}
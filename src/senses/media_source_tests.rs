@@ -0,0 +1,88 @@
+//! Tests of recognising the kind and the clip of media from their paths.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use senses::error::SensesError;
+use senses::media_source::{MediaKind, MediaSource};
+
+/// Empty directory of its own in the temporary directory.
+fn directory(name: &str) -> PathBuf {
+  let directory = env::temp_dir().join(format!("media-{}-{}", name, process::id()));
+  let _ = fs::remove_dir_all(&directory);
+  fs::create_dir_all(&directory).unwrap();
+
+  directory
+}
+
+#[test]
+fn extension_decides_the_kind() {
+  let kinds = [
+    ("frame.png", MediaKind::Frame),
+    ("frame.JPG", MediaKind::Frame),
+    ("frame.jpeg", MediaKind::Frame),
+    ("frame.Bmp", MediaKind::Frame),
+    ("clip.mp4", MediaKind::Video),
+    ("clip.MKV", MediaKind::Video),
+    ("sound.wav", MediaKind::Wav),
+    ("sound.WAV", MediaKind::Wav),
+  ];
+
+  for (path, kind) in kinds.iter() {
+    assert_eq!(MediaSource::from_path(*path).unwrap().kind(), *kind, "{}", path);
+  }
+}
+
+#[test]
+fn unsupported_files_are_an_error() {
+  for path in ["notes.txt", "sound.mp3", "no-extension"].iter() {
+    match MediaSource::from_path(*path) {
+      Err(SensesError::UnsupportedMedia(unsupported)) => assert_eq!(unsupported, PathBuf::from(path)),
+      other => panic!("{} is {:?}", path, other),
+    }
+  }
+}
+
+#[test]
+fn clip_is_the_parent_of_the_video_or_audio_directory() {
+  let frame = MediaSource::from_path("data/debug-1/video/output_0001.png").unwrap();
+  assert_eq!(frame.clip(), Some("debug-1"));
+
+  let wav = MediaSource::from_path("data/debug-1/audio/note-e.wav").unwrap();
+  assert_eq!(wav.clip(), Some("debug-1"));
+
+  // Media outside of the layout of the data directory have no clip.
+  assert_eq!(MediaSource::from_path("frame.png").unwrap().clip(), None);
+  assert_eq!(MediaSource::from_path("data/debug-1/frame.png").unwrap().clip(), None);
+}
+
+#[test]
+fn frames_of_a_sequence_are_in_order() {
+  let root = directory("frames");
+  let video = root.join("clip").join("video");
+  fs::create_dir_all(&video).unwrap();
+
+  for name in ["output_0003.png", "output_0001.png", "notes.txt", "output_0002.jpg", "sound.wav"].iter() {
+    fs::write(video.join(name), b"").unwrap();
+  }
+
+  let sequence = MediaSource::from_path(&video).unwrap();
+  assert_eq!(sequence.kind(), MediaKind::FrameSequence);
+
+  let frames = sequence.frames().unwrap();
+  let names: Vec<String> = frames.iter()
+    .map(|frame| frame.path().file_name().unwrap().to_string_lossy().into_owned())
+    .collect();
+
+  assert_eq!(names, vec!("output_0001.png", "output_0002.jpg", "output_0003.png"));
+  assert!(frames.iter().all(|frame| frame.kind() == MediaKind::Frame && frame.clip() == Some("clip")));
+
+  // Single frame is a sequence of itself, other media have no frames.
+  let frame = MediaSource::from_path(video.join("output_0001.png")).unwrap();
+  assert_eq!(frame.frames().unwrap().len(), 1);
+  assert!(MediaSource::from_path(video.join("sound.wav")).unwrap().frames().is_err());
+
+  fs::remove_dir_all(&root).unwrap();
+}
@@ -2,20 +2,16 @@
 //! This module will eventually be extracted out and the preprocessing will
 //! happen on a dedicated machine.
 
-mod file;
-mod visual;
-mod auditory;
+mod error;
+mod dataset;
+mod media_source;
+pub mod visual;
+pub mod auditory;
 
-use self::file::File;
-//use self::auditory::sound;
-use self::visual::identify_objects;
+#[cfg(test)]
+mod media_source_tests;
+#[cfg(test)]
+mod dataset_tests;
 
-pub fn start_data_channel() {
-  identify_objects(
-    File::new(
-      String::from("data/debug-1/video"),
-      String::from("output_0407"),
-      String::from("png"),
-    )
-  );
-}
+pub use self::dataset::{Clip, ClipIssue, Dataset};
+pub use self::media_source::{MediaKind, MediaSource};
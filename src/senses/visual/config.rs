@@ -0,0 +1,105 @@
+use std::str::FromStr;
+use senses::visual::heat_map::CELL_SIZE;
+use senses::visual::morphology::StructuringElement;
+
+/// Which map feeds the cellular automaton with heat.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HeatSource {
+
+  /// Density of edges found by `find_edges`. Treats all texture as salient.
+  Edges,
+
+  /// Spectral residual saliency of the image resampled onto the cell grid.
+  Saliency,
+
+  /// Edge density weighted by the saliency of the cell, which suppresses
+  /// textured background such as grass or foliage.
+  Combined,
+
+}
+
+impl FromStr for HeatSource {
+
+  type Err = String;
+
+  fn from_str(name: &str) -> Result<HeatSource, String> {
+    match name {
+      "edges" => Ok(HeatSource::Edges),
+      "saliency" => Ok(HeatSource::Saliency),
+      "combined" => Ok(HeatSource::Combined),
+      _ => Err(format!("Unknown heat source {}", name)),
+    }
+  }
+
+}
+
+/// How `extract_highlights` splits objects that are too large.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SplittingStrategy {
+
+  /// Sets to off every point that does not have all of its Moore neighbours on.
+  Peel,
+
+  /// Erodes the object with given structuring element.
+  Erode(StructuringElement),
+
+  /// Opens the object with given structuring element. This cuts thin
+  /// connections between parts of the object while keeping their size.
+  Open(StructuringElement),
+
+  /// Separates the object along its narrowest necks with distance transform
+  /// watershed. No points of the object are removed.
+  Watershed,
+
+}
+
+/// How highlights are cut out of the image.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CropMode {
+
+  /// Axis aligned rectangle around the object expanded by one cell.
+  Rectangle,
+
+  /// Same rectangle in RGBA where the pixels outside of the cells of the
+  /// object are transparent. The edge of the mask fades out over feather
+  /// pixels, 0 keeps the edge sharp.
+  Masked { feather: u32 },
+
+}
+
+/// Settings of the visual pipeline.
+#[derive(Clone, Debug)]
+pub struct VisualConfig {
+
+  /// Size of the side of a heat map cell in pixels. Both dimensions of the
+  /// image have to be divisible by it.
+  pub cell_size: u32,
+
+  /// Source of the heat map that is stabilized by the automaton.
+  pub heat_source: HeatSource,
+
+  /// Whether to keep the channels of each edge orientation and calculate
+  /// orientation histogram for each highlight.
+  pub edge_orientations: bool,
+
+  /// How objects larger than MAX_CELLS are split into smaller ones.
+  pub splitting: SplittingStrategy,
+
+  /// Whether highlights are cut out as rectangles or masked.
+  pub crop: CropMode,
+
+}
+
+impl Default for VisualConfig {
+
+  fn default() -> VisualConfig {
+    VisualConfig {
+      cell_size: CELL_SIZE,
+      heat_source: HeatSource::Edges,
+      edge_orientations: false,
+      splitting: SplittingStrategy::Peel,
+      crop: CropMode::Rectangle,
+    }
+  }
+
+}
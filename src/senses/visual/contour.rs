@@ -0,0 +1,282 @@
+use std::f32::consts::PI;
+use senses::visual::point::Point;
+use senses::visual::helpers::pixel_value;
+use senses::visual::morphology::count_highlighted;
+
+type PointMap = Vec<Vec<bool>>;
+
+/// Moore neighbourhood clockwise starting from the point above.
+const NEIGHBOURHOOD: [(isize, isize); 8] = [
+  (0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1),
+];
+
+/// Rectangle that is not necessarily aligned with the axes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RotatedRect {
+
+  /// Centre of the rectangle.
+  pub center: (f32, f32),
+
+  /// Length of the longer side.
+  pub width: f32,
+
+  /// Length of the shorter side.
+  pub height: f32,
+
+  /// Angle between the longer side and the x axis in radians, in range of 0
+  /// to PI.
+  pub angle: f32,
+
+}
+
+/// Traces the outer boundary of the first object in the map (in the order the
+/// rows are read) with Moore neighbour tracing. The boundary is walked
+/// clockwise starting with the top left point of the object and the tracing
+/// stops once the start point is entered again the same way it was first
+/// entered (Jacob's stopping criterion).
+pub fn trace_contour(map: &PointMap) -> Vec<Point> {
+  let start = map.iter().enumerate().filter_map(
+    |(y, row)| row.iter().position(|highlighted| *highlighted).map(|x| (x as isize, y as isize))
+  ).next();
+
+  let start = match start {
+    None => return Vec::new(),
+    Some(start) => start,
+  };
+
+  // The point left of the start is never highlighted as the start is the first
+  // highlighted point of its row.
+  let start_backtrack = (start.0 - 1, start.1);
+
+  let mut contour: Vec<Point> = vec!(Point::new(start.0 as u32, start.1 as u32));
+  let mut current = start;
+  let mut backtrack = start_backtrack;
+  let mut first_step: Option<((isize, isize), (isize, isize))> = None;
+  // Guards against walking around forever should the start never be entered
+  // in the same way.
+  let max_length = 4 * count_highlighted(map);
+
+  while contour.len() <= max_length {
+    let backtrack_direction = NEIGHBOURHOOD.iter()
+      .position(|offset| (current.0 + offset.0, current.1 + offset.1) == backtrack)
+      .unwrap_or(6);
+
+    // Goes clockwise around the current point from the backtrack until it
+    // finds a highlighted point.
+    let next = (1..9).map(|step| (backtrack_direction + step) % 8).find(|direction| {
+      let (offset_x, offset_y) = NEIGHBOURHOOD[*direction];
+
+      pixel_value(map, current.0 + offset_x, current.1 + offset_y, false)
+    });
+
+    let direction = match next {
+      // Object is a single point.
+      None => break,
+      Some(direction) => direction,
+    };
+
+    // The point checked right before the found one is the new backtrack.
+    let (previous_x, previous_y) = NEIGHBOURHOOD[(direction + 7) % 8];
+    backtrack = (current.0 + previous_x, current.1 + previous_y);
+
+    let (offset_x, offset_y) = NEIGHBOURHOOD[direction];
+    current = (current.0 + offset_x, current.1 + offset_y);
+
+    // Thin objects might never enter the start the same way, but the walk
+    // repeats itself once the first step is made again.
+    if current == start && backtrack == start_backtrack || first_step == Some((current, backtrack)) {
+      break;
+    }
+
+    if first_step.is_none() {
+      first_step = Some((current, backtrack));
+    }
+
+    // Start might be entered from a different direction several times, but
+    // it is only a single point of the contour.
+    if current != start {
+      contour.push(Point::new(current.0 as u32, current.1 as u32));
+    }
+  }
+
+  contour
+}
+
+/// Simplifies a closed contour into a polygon with Douglas-Peucker algorithm.
+/// Points that are closer than epsilon to the line between the points that
+/// are kept are removed.
+pub fn simplify_polygon(contour: &[Point], epsilon: f32) -> Vec<Point> {
+  if contour.len() < 3 {
+    return contour.to_vec();
+  }
+
+  // Closed contour is split into two chains at the point furthest from the
+  // start, so that neither chain starts and ends in the same point.
+  let furthest = (1..contour.len()).fold(0, |furthest, index| {
+    if distance(contour[0], contour[index]) > distance(contour[0], contour[furthest]) {
+      index
+    } else {
+      furthest
+    }
+  });
+
+  let mut closed_chain: Vec<Point> = contour[furthest..].to_vec();
+  closed_chain.push(contour[0]);
+
+  let mut polygon = douglas_peucker(&contour[..(furthest + 1)], epsilon);
+  polygon.pop();
+  polygon.extend(douglas_peucker(&closed_chain, epsilon));
+  polygon.pop();
+
+  polygon
+}
+
+/// Finds the smallest convex polygon that contains all points using Andrew's
+/// monotone chain algorithm. The hull is ordered clockwise on the screen.
+pub fn convex_hull(points: &[Point]) -> Vec<Point> {
+  let mut sorted: Vec<Point> = points.to_vec();
+  sorted.sort_by_key(|point| (point.x, point.y));
+  sorted.dedup();
+
+  if sorted.len() < 3 {
+    return sorted;
+  }
+
+  let mut hull = half_hull(sorted.iter());
+  hull.extend(half_hull(sorted.iter().rev()));
+
+  hull
+}
+
+/// Finds the rectangle of the smallest area that contains the convex hull.
+/// One of the sides of such rectangle always lies on an edge of the hull,
+/// therefore only rotations given by the edges are tried.
+pub fn min_area_rect(hull: &[Point]) -> Option<RotatedRect> {
+  if hull.is_empty() {
+    return None;
+  }
+
+  let mut best: Option<(f32, RotatedRect)> = None;
+
+  for index in 0..hull.len() {
+    let from = hull[index];
+    let to = hull[(index + 1) % hull.len()];
+    let angle = (to.y as f32 - from.y as f32).atan2(to.x as f32 - from.x as f32);
+    let (sin, cos) = angle.sin_cos();
+
+    // Rotates the hull so that the edge is aligned with the x axis and finds
+    // the bounding box.
+    let (min_u, min_v, max_u, max_v) = hull.iter().fold(
+      (f32::MAX, f32::MAX, f32::MIN, f32::MIN),
+      |(min_u, min_v, max_u, max_v), point| {
+        let u = point.x as f32 * cos + point.y as f32 * sin;
+        let v = -(point.x as f32) * sin + point.y as f32 * cos;
+
+        (min_u.min(u), min_v.min(v), max_u.max(u), max_v.max(v))
+      },
+    );
+
+    let area = (max_u - min_u) * (max_v - min_v);
+    if best.is_some_and(|(best_area, _)| best_area <= area) {
+      continue;
+    }
+
+    // Rotates the centre of the box back.
+    let (center_u, center_v) = ((min_u + max_u) / 2_f32, (min_v + max_v) / 2_f32);
+    let center = (center_u * cos - center_v * sin, center_u * sin + center_v * cos);
+
+    let rect = if max_u - min_u >= max_v - min_v {
+      RotatedRect { center, width: max_u - min_u, height: max_v - min_v, angle }
+    } else {
+      RotatedRect { center, width: max_v - min_v, height: max_u - min_u, angle: angle + PI / 2_f32 }
+    };
+
+    best = Some((area, rect));
+  }
+
+  best.map(|(_, rect)| RotatedRect {
+    angle: ((rect.angle % PI) + PI) % PI,
+    ..rect
+  })
+}
+
+/// Builds the upper or lower half of the convex hull from sorted points,
+/// popping points that would make a counter clockwise turn on the screen.
+fn half_hull<'a, I>(points: I) -> Vec<Point>
+  where I: Iterator<Item = &'a Point> {
+  let mut hull: Vec<Point> = Vec::new();
+
+  for point in points {
+    while hull.len() >= 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], *point) <= 0_f32 {
+      hull.pop();
+    }
+
+    hull.push(*point);
+  }
+
+  // Last point is the first point of the other half.
+  hull.pop();
+  hull
+}
+
+/// Simplifies an open chain of points, always keeping its first and last point.
+fn douglas_peucker(chain: &[Point], epsilon: f32) -> Vec<Point> {
+  if chain.len() < 3 {
+    return chain.to_vec();
+  }
+
+  let first = chain[0];
+  let last = chain[chain.len() - 1];
+
+  let (furthest, max_distance) = (1..(chain.len() - 1)).fold((0, 0_f32), |(furthest, max), index| {
+    let distance = distance_to_segment(chain[index], first, last);
+
+    if distance > max { (index, distance) } else { (furthest, max) }
+  });
+
+  if max_distance <= epsilon {
+    return vec!(first, last);
+  }
+
+  let mut simplified = douglas_peucker(&chain[..(furthest + 1)], epsilon);
+  simplified.pop();
+  simplified.extend(douglas_peucker(&chain[furthest..], epsilon));
+
+  simplified
+}
+
+/// Distance of a point from the line segment between two points. A point
+/// beyond either end of the segment is measured from that end.
+pub fn distance_to_segment(point: Point, from: Point, to: Point) -> f32 {
+  let length = distance(from, to);
+
+  if length == 0_f32 {
+    return distance(point, from);
+  }
+
+  // Position of the point projected onto the line, from 0 at the start of the
+  // segment to 1 at its end.
+  let (direction_x, direction_y) = (to.x as f32 - from.x as f32, to.y as f32 - from.y as f32);
+  let (offset_x, offset_y) = (point.x as f32 - from.x as f32, point.y as f32 - from.y as f32);
+  let along = (offset_x * direction_x + offset_y * direction_y) / (length * length);
+
+  if along <= 0_f32 {
+    distance(point, from)
+  } else if along >= 1_f32 {
+    distance(point, to)
+  } else {
+    cross(from, to, point).abs() / length
+  }
+}
+
+/// Z coordinate of the cross product of vectors origin->a and origin->b. It is
+/// positive if the points make a clockwise turn on the screen.
+fn cross(origin: Point, a: Point, b: Point) -> f32 {
+  let (origin_x, origin_y) = (origin.x as f32, origin.y as f32);
+
+  (a.x as f32 - origin_x) * (b.y as f32 - origin_y) - (a.y as f32 - origin_y) * (b.x as f32 - origin_x)
+}
+
+fn distance(a: Point, b: Point) -> f32 {
+  ((a.x as f32 - b.x as f32).powi(2) + (a.y as f32 - b.y as f32).powi(2)).sqrt()
+}
@@ -0,0 +1,153 @@
+//! Tests of the contour tracing and the shape features on known shapes.
+
+use std::env;
+use std::f32::consts::PI;
+use std::fs;
+use std::process;
+
+use senses::visual::contour::{convex_hull, distance_to_segment, min_area_rect, simplify_polygon, trace_contour};
+use senses::visual::point::Point;
+use senses::visual::visual_object::{save_objects, VisualObject};
+
+type PointMap = Vec<Vec<bool>>;
+
+fn map(rows: &[&str]) -> PointMap {
+  rows.iter().map(|row| row.chars().map(|point| point == '#').collect()).collect()
+}
+
+fn points(coordinates: &[(u32, u32)]) -> Vec<Point> {
+  coordinates.iter().map(|(x, y)| Point::new(*x, *y)).collect()
+}
+
+#[test]
+fn contour_of_square_goes_clockwise_around_its_border() {
+  let square = map(&[
+    ".....",
+    ".###.",
+    ".###.",
+    ".###.",
+    ".....",
+  ]);
+
+  assert_eq!(trace_contour(&square), points(&[
+    (1, 1), (2, 1), (3, 1), (3, 2), (3, 3), (2, 3), (1, 3), (1, 2),
+  ]));
+}
+
+#[test]
+fn contour_of_single_point_is_the_point() {
+  assert_eq!(trace_contour(&map(&["...", ".#.", "..."])), points(&[(1, 1)]));
+  assert!(trace_contour(&map(&["...", "..."])).is_empty());
+}
+
+#[test]
+fn square_contour_simplifies_to_corners() {
+  let square = map(&[
+    ".......",
+    ".#####.",
+    ".#####.",
+    ".#####.",
+    ".#####.",
+    ".#####.",
+    ".......",
+  ]);
+
+  let mut polygon = simplify_polygon(&trace_contour(&square), 0.5_f32);
+  polygon.sort_by_key(|point| (point.x, point.y));
+
+  assert_eq!(polygon, points(&[(1, 1), (1, 5), (5, 1), (5, 5)]));
+}
+
+#[test]
+fn hull_of_collinear_points_is_their_ends() {
+  let line = points(&[(0, 0), (1, 1), (2, 2), (3, 3), (2, 2)]);
+
+  assert_eq!(convex_hull(&line), points(&[(0, 0), (3, 3)]));
+}
+
+#[test]
+fn hull_drops_inner_and_edge_points() {
+  let square = points(&[(0, 0), (2, 0), (4, 0), (0, 4), (4, 4), (2, 2), (4, 2), (1, 3)]);
+  let hull = convex_hull(&square);
+
+  assert_eq!(hull.len(), 4);
+  for corner in points(&[(0, 0), (4, 0), (0, 4), (4, 4)]) {
+    assert!(hull.contains(&corner), "{:?} is not in {:?}", corner, hull);
+  }
+}
+
+#[test]
+fn rotated_rectangle_has_its_area_and_angle() {
+  // Rectangle with sides of 2 * sqrt(2) and 3 * sqrt(2) rotated by 45 degrees.
+  let corners = points(&[(0, 2), (2, 0), (5, 3), (3, 5)]);
+  let rect = min_area_rect(&convex_hull(&corners)).unwrap();
+
+  assert!((rect.width * rect.height - 12_f32).abs() < 1e-3, "area is {}", rect.width * rect.height);
+  assert!((rect.width - 3_f32 * 2_f32.sqrt()).abs() < 1e-3);
+  assert!((rect.height - 2_f32 * 2_f32.sqrt()).abs() < 1e-3);
+  assert!((rect.angle - PI / 4_f32).abs() < 1e-3, "angle is {}", rect.angle);
+  assert!((rect.center.0 - 2.5_f32).abs() < 1e-3 && (rect.center.1 - 2.5_f32).abs() < 1e-3);
+}
+
+#[test]
+fn rectangle_of_nothing_is_none() {
+  assert_eq!(min_area_rect(&[]), None);
+}
+
+#[test]
+fn distance_beyond_the_end_of_a_segment_is_to_that_end() {
+  let (from, to) = (Point::new(0, 0), Point::new(4, 0));
+
+  // Beside the segment the distance is to the line.
+  assert_eq!(distance_to_segment(Point::new(2, 3), from, to), 3_f32);
+
+  // Point on the line through the segment but past its end is not on it.
+  assert_eq!(distance_to_segment(Point::new(10, 0), from, to), 6_f32);
+  assert_eq!(distance_to_segment(Point::new(7, 4), from, to), 5_f32);
+  assert_eq!(distance_to_segment(Point::new(0, 5), to, from), 5_f32);
+
+  assert_eq!(distance_to_segment(Point::new(3, 4), from, from), 5_f32);
+}
+
+#[test]
+fn point_on_the_line_of_a_chord_but_past_its_end_is_kept() {
+  // After the split at (15, 0), the chord runs from (5, 0) to (15, 0) and
+  // (0, 0) lies on its line, five points before its start.
+  let contour = points(&[(5, 0), (0, 0), (15, 0), (15, 5)]);
+  let polygon = simplify_polygon(&contour, 3_f32);
+
+  assert_eq!(polygon, contour);
+}
+
+#[test]
+fn csv_has_a_row_with_the_shape_of_each_leaf() {
+  // Bar of 6x3 cells split into its halves, the right one offset by its
+  // reference.
+  let mut left = VisualObject::new(Point::new(10, 10));
+  let mut right = VisualObject::new(Point::new(13, 10));
+  for point in (0..9).map(|index| Point::new(index % 3, index / 3)) {
+    left.push(point);
+    right.push(point);
+  }
+
+  let mut bar = VisualObject::new(Point::new(10, 10));
+  bar.children = vec!(left, right);
+
+  let path = env::temp_dir().join(format!("objects-{}.csv", process::id()));
+  save_objects(&mut [bar], &path).unwrap();
+  let csv = fs::read_to_string(&path).unwrap();
+  fs::remove_file(&path).unwrap();
+
+  let rows: Vec<Vec<&str>> = csv.lines().skip(1).map(|row| row.split(',').collect()).collect();
+  assert_eq!(rows.len(), 2);
+
+  // Index, bounding box, cells and corners of the polygon.
+  assert_eq!(rows[1][..7], ["1", "13", "10", "15", "12", "9", "4"]);
+
+  // The centre of the rotated rectangle is in the cells of the frame too.
+  let center: Vec<f32> = rows[1][7..9].iter().map(|value| value.parse().unwrap()).collect();
+  assert!((center[0] - 14_f32).abs() < 1e-4 && (center[1] - 11_f32).abs() < 1e-4, "{:?}", center);
+
+  // Orientations were not counted.
+  assert!(rows[1][12..].iter().all(|value| value.is_empty()));
+}
@@ -0,0 +1,101 @@
+//! Tests of cutting highlights out of the frame, in particular of objects that
+//! touch the border of the frame, where the crop cannot be expanded.
+
+use std::env;
+use std::fs;
+use std::process;
+
+use senses::media_source::{MediaKind, MediaSource};
+use senses::visual::config::CropMode;
+use senses::visual::cut_highlights_from_image::cut_highlights_from_image;
+use senses::visual::image::{DynamicImage, GenericImageView, ImageRgb8, Rgb, RgbImage};
+use senses::visual::point::Point;
+use senses::visual::visual_object::VisualObject;
+use senses::visual::{identify_objects, VisualConfig};
+
+const CELL_SIZE: u32 = 10;
+
+fn frame() -> DynamicImage {
+  ImageRgb8(RgbImage::from_pixel(60, 60, Rgb([200, 200, 200])))
+}
+
+/// Object of given cells with its reference in the top left corner.
+fn object(cells: &[(u32, u32)]) -> VisualObject {
+  let mut object = VisualObject::new(Point::new(0, 0));
+
+  for (x, y) in cells {
+    object.push(Point::new(*x, *y));
+  }
+
+  object
+}
+
+#[test]
+fn crop_is_expanded_by_a_cell_inside_the_frame() {
+  let mut objects = vec!(object(&[(2, 2), (3, 3)]));
+  let crops = cut_highlights_from_image(&mut objects, frame(), &CropMode::Rectangle, CELL_SIZE);
+
+  // Cells start every 5 pixels, the crop starts at cell 1 and ends at cell 4.
+  assert_eq!(crops[0].dimensions(), (15, 15));
+}
+
+#[test]
+fn crop_of_object_in_the_corner_stops_at_the_border() {
+  for mode in [CropMode::Rectangle, CropMode::Masked { feather: 2 }].iter() {
+    let mut objects = vec!(object(&[(0, 0), (1, 0), (0, 1), (1, 1)]));
+    let crops = cut_highlights_from_image(&mut objects, frame(), mode, CELL_SIZE);
+
+    // There is no cell before the first one, the crop starts at cell 0.
+    assert_eq!(crops[0].dimensions(), (10, 10), "{:?}", mode);
+  }
+}
+
+#[test]
+fn mask_of_object_in_the_corner_covers_its_cells() {
+  let mut objects = vec!(object(&[(0, 0), (1, 1)]));
+  let crops = cut_highlights_from_image(
+    &mut objects,
+    frame(),
+    &CropMode::Masked { feather: 0 },
+    CELL_SIZE,
+  );
+  let mask = crops[0].to_rgba();
+
+  // Cells of the object are opaque, the cells next to them are transparent.
+  assert_eq!(mask.get_pixel(2, 2).data[3], 255);
+  assert_eq!(mask.get_pixel(7, 7).data[3], 255);
+  assert_eq!(mask.get_pixel(7, 2).data[3], 0);
+  assert_eq!(mask.get_pixel(2, 7).data[3], 0);
+}
+
+#[test]
+fn objects_in_the_corner_of_a_frame_are_cut_out() {
+  let directory = env::temp_dir().join(format!("crop-corner-{}", process::id()));
+  fs::create_dir_all(&directory).unwrap();
+
+  // Bright frame with a striped patch in the top left corner.
+  let frame = RgbImage::from_fn(80, 80, |x, y| {
+    if x < 30 && y < 30 && (x / 3) % 2 == 0 {
+      Rgb([0, 0, 0])
+    } else {
+      Rgb([255, 255, 255])
+    }
+  });
+  let path = directory.join("frame.png");
+  frame.save(&path).unwrap();
+  let source = MediaSource::new(path, MediaKind::Frame);
+
+  for mode in [CropMode::Rectangle, CropMode::Masked { feather: 2 }].iter() {
+    let config = VisualConfig { crop: *mode, ..VisualConfig::default() };
+    let mut objects = identify_objects(&source, &config, Some(&directory)).unwrap();
+
+    let on_border = objects.iter_mut().flat_map(|object| object.leaves_mut()).any(|leaf| {
+      let reference = leaf.reference;
+
+      leaf.size().is_some_and(|(lower, _)| reference.x + lower.x == 0 || reference.y + lower.y == 0)
+    });
+    assert!(on_border, "{:?}: no object on the border", mode);
+  }
+
+  fs::remove_dir_all(&directory).unwrap();
+}
@@ -1,21 +1,83 @@
-use senses::visual::heat_map::CELL_SIZE;
-use senses::visual::image::{DynamicImage};
+use senses::visual::config::CropMode;
+use senses::visual::point::Point;
+use senses::visual::heat_map::cell_to_pixel;
+use senses::visual::morphology::distance_transform;
+use senses::visual::image::{DynamicImage, ImageRgba8};
 use senses::visual::visual_object::VisualObject;
 
+/// Crops the rectangle around each leaf highlight expanded by one cell in each
+/// direction from the image. In masked mode, pixels outside of the cells of
+/// the highlight are transparent.
 pub fn cut_highlights_from_image(
-  mut highlights: Vec<VisualObject>,
+  highlights: &mut [VisualObject],
   mut image: DynamicImage,
+  mode: &CropMode,
+  cell_size: u32,
 ) -> Vec<DynamicImage> {
-  highlights.iter_mut().filter_map(|highlight| {
+  highlights.iter_mut().flat_map(|highlight| highlight.leaves_mut()).filter_map(|highlight| {
     let (lower, higher) = highlight.size()?;
     let lower = lower + highlight.reference;
     let higher = higher + highlight.reference;
 
-    Some(image.crop(
-      (lower.x - 1) * CELL_SIZE / 2,
-      (lower.y - 1) * CELL_SIZE / 2,
-      (higher.x - lower.x + 2) * CELL_SIZE / 2,
-      (higher.y - lower.y + 2) * CELL_SIZE / 2
-    ))
+    // Highlights on the top or left border have no cell before them to expand
+    // the crop by.
+    let corner = Point::new(
+      lower.x.saturating_sub(1) * cell_size / 2,
+      lower.y.saturating_sub(1) * cell_size / 2,
+    );
+    let crop = image.crop(
+      corner.x,
+      corner.y,
+      (higher.x + 1) * cell_size / 2 - corner.x,
+      (higher.y + 1) * cell_size / 2 - corner.y
+    );
+
+    Some(match mode {
+      CropMode::Rectangle => crop,
+      CropMode::Masked { feather } => mask_crop(&crop, highlight, corner, *feather, cell_size),
+    })
   }).collect()
 }
+
+/// Converts the crop to RGBA where the alpha channel is given by the cells of
+/// the highlight upscaled to pixels. With feathering, the alpha fades in over
+/// given number of pixels from the edge of the mask.
+fn mask_crop(
+  crop: &DynamicImage,
+  highlight: &VisualObject,
+  corner: Point,
+  feather: u32,
+  cell_size: u32,
+) -> DynamicImage {
+  let mut masked = crop.to_rgba();
+  let (width, height) = masked.dimensions();
+
+  // Each cell of the highlight stands for a block of pixels in the image.
+  let mut mask: Vec<Vec<bool>> = vec!(vec!(false; width as usize); height as usize);
+  for point in highlight.points.iter() {
+    let pixel = cell_to_pixel(*point + highlight.reference, cell_size);
+
+    for y in pixel.y..(pixel.y + cell_size / 2) {
+      for x in pixel.x..(pixel.x + cell_size / 2) {
+        if x >= corner.x && y >= corner.y && x - corner.x < width && y - corner.y < height {
+          mask[(y - corner.y) as usize][(x - corner.x) as usize] = true;
+        }
+      }
+    }
+  }
+
+  let distances = distance_transform(&mask);
+
+  for (x, y, pixel) in masked.enumerate_pixels_mut() {
+    let distance = distances[y as usize][x as usize];
+    let opacity = if feather == 0 {
+      distance.min(1_f32)
+    } else {
+      (distance / feather as f32).min(1_f32)
+    };
+
+    pixel.data[3] = (pixel.data[3] as f32 * opacity).round() as u8;
+  }
+
+  ImageRgba8(masked)
+}
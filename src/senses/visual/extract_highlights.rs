@@ -1,15 +1,21 @@
 use senses::visual::point::Point;
-use senses::visual::heat_map::CELL_SIZE;
 use senses::visual::helpers::pixel_value;
+use senses::visual::config::SplittingStrategy;
+use senses::visual::watershed::watershed;
 use senses::visual::visual_object::VisualObject;
+use senses::visual::morphology::{count_highlighted, erode, open};
 
 type PointMap = Vec<Vec<bool>>;
 
 pub const MAX_CELLS: u32 = 40;
 
+/// Finds objects in the map and splits those that are larger than MAX_CELLS in
+/// either direction with given strategy until they are small enough. The
+/// result is a tree where each large object holds the parts it was split into.
 pub fn extract_highlights(
   image: PointMap,
   reference: Point,
+  strategy: &SplittingStrategy,
   objects: &mut Vec<VisualObject>,
 ) {
   for mut highlight in find_highlights_in_map(image, reference) {
@@ -30,79 +36,100 @@ pub fn extract_highlights(
       continue;
     }
 
-    match highlight.peeled_map() {
-      None => continue,
-      Some(map) => extract_highlights(map, highlight.reference + lower, objects),
-    };
+    // The object is too large, therefore it is split into smaller ones which
+    // become its children. The object is kept only if any of its parts was
+    // large enough to be an object.
+    let mut children: Vec<VisualObject> = Vec::new();
+    for map in split_map(&mut highlight, strategy) {
+      extract_highlights(map, highlight.reference + lower, strategy, &mut children);
+    }
+
+    if !children.is_empty() {
+      highlight.children = children;
+      objects.push(highlight);
+    }
+  }
+}
+
+/// Creates point maps of the parts the object is split into by given strategy.
+/// Should the strategy not split or remove anything from the object (e.g.
+/// opening an object without thin parts), the object is peeled instead so that
+/// the recursion always ends.
+fn split_map(highlight: &mut VisualObject, strategy: &SplittingStrategy) -> Vec<PointMap> {
+  let map = match highlight.point_map() {
+    None => return Vec::new(),
+    Some(map) => map,
+  };
+
+  let split = match strategy {
+    SplittingStrategy::Peel => Vec::new(),
+    SplittingStrategy::Erode(element) => vec!(erode(&map, element)),
+    SplittingStrategy::Open(element) => vec!(open(&map, element)),
+    SplittingStrategy::Watershed => watershed(&map),
+  };
+
+  let is_split = split.len() > 1 || split.iter().any(
+    |part| count_highlighted(part) < count_highlighted(&map)
+  );
+
+  if is_split {
+    split
+  } else {
+    highlight.peeled_map().into_iter().collect()
   }
 }
 
 /// Finds objects within given image heatmap. Uses flood fill algorithm which,
 /// after finding any highlighted unvisited point within the image, selects all
-/// highlighted other points in the neighbourhood. This happens recursively for
-/// each highlighted unvisited point.
-fn find_highlights_in_map(mut image: PointMap, reference: Point) -> Vec<VisualObject> {
-  // Currently iterated point in the image.
-  let mut current_point: Point = Point::new(0, 0);
+/// highlighted other points in the neighbourhood, and then their neighbours
+/// until the whole object is selected.
+pub fn find_highlights_in_map(mut image: PointMap, reference: Point) -> Vec<VisualObject> {
   // Instantiates the return vector.
   let mut objects: Vec<VisualObject> = Vec::new();
 
-  // Should the image be empty, return empty vector.
-  if image.len() == 0 || image[0].len() == 0 {
-    return objects;
-  }
-
-  // Servers as image dimensions.
-  let last_point: Point = Point::new(
-    image[0].len() as u32 - 1,
-    image.len() as u32 - 1,
-  );
-
-  // As long as the currently iterated point is not the last one, run the cycle.
-  while current_point != last_point {
-    // If the value at currently iterated point is positive, flood fill the
-    // object and remove it from the original map.
-    if pixel_value(&image, current_point.x as isize, current_point.y as isize, false) {
-      let mut object: VisualObject = VisualObject::new(reference);
-      flood_fill(current_point, &mut object, &mut image);
-      objects.push(object);
-    }
-
-    // Increments the row starting from 0 if current_point reached the end of
-    // the line otherwise moves to the pixel to the right.
-    if current_point.x == last_point.x {
-      current_point.x = 0;
-      current_point.y += 1;
-    } else {
-      current_point.x += 1;
+  // Visits every point of the image row by row, including the last one.
+  for y in 0..image.len() {
+    for x in 0..image[y].len() {
+      // If the value at currently iterated point is positive, flood fill the
+      // object and remove it from the original map.
+      if image[y][x] {
+        let mut object: VisualObject = VisualObject::new(reference);
+        flood_fill(Point::new(x as u32, y as u32), &mut object, &mut image);
+        objects.push(object);
+      }
     }
   }
 
   objects
 }
 
-/// Recursively finds a single object within given image. It calls this function
-/// for every new highlighted point.
-fn flood_fill(point: Point, object: &mut VisualObject, image: &mut PointMap) {
-  // Adds currently iterated point to the object and set that point to no
-  // highlighted.
-  object.push(point);
-  image[point.y as usize][point.x as usize] = false;
-
-  // Iterates over the Moore neighbourhood of currently iterated point.
-  for y in (point.y as isize - 1)..(point.y as isize + 2) {
-    if y < 0 {
-      continue;
-    }
-
-    for x in (point.x as isize - 1)..(point.x as isize + 2) {
-      // If the Moore's point is not highlighted, skips.
-      if x < 0 || !pixel_value(image, x, y, false) {
+/// Finds a single object within given image with an explicit stack of the
+/// points whose neighbourhood is yet to be visited, so that large objects do
+/// not overflow the call stack.
+fn flood_fill(start: Point, object: &mut VisualObject, image: &mut PointMap) {
+  // Points are set to not highlighted as soon as they are found, so that each
+  // of them is added to the object once.
+  let mut stack: Vec<Point> = vec!(start);
+  image[start.y as usize][start.x as usize] = false;
+
+  while let Some(point) = stack.pop() {
+    object.push(point);
+
+    // Iterates over the Moore neighbourhood of currently iterated point.
+    for y in (point.y as isize - 1)..(point.y as isize + 2) {
+      if y < 0 {
         continue;
       }
 
-      // Visits the Moore's point.
-      flood_fill(Point::new(x as u32, y as u32), object, image);
+      for x in (point.x as isize - 1)..(point.x as isize + 2) {
+        // If the Moore's point is not highlighted, skips.
+        if x < 0 || !pixel_value(image, x, y, false) {
+          continue;
+        }
+
+        image[y as usize][x as usize] = false;
+        stack.push(Point::new(x as u32, y as u32));
+      }
     }
   }
 }
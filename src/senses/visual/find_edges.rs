@@ -48,47 +48,99 @@ const FILTERS: [[f32; 9]; 5] = [
   ],
 ];
 
-/// Finds edges in given grayscale picture by using two 3x3 matrixes. First one
-/// detects horizontal edges, the second one vertical.
+/// Number of edge orientations the filter bank distinguishes.
+pub const ORIENTATIONS: usize = 5;
+
+/// Edges found by each of the oriented FILTERS, kept in separate channels in
+/// the same order as the filters. Each channel has white background with dark
+/// edges highlighted.
+pub struct OrientedEdges {
+  pub channels: Vec<GrayImage>,
+}
+
+impl OrientedEdges {
+
+  /// Merges all channels into a single image. A pixel is an edge if it was
+  /// recognized as one by any of the filters.
+  pub fn merged(&self) -> GrayImage {
+    let (width, height) = self.channels[0].dimensions();
+    let mut edge_detector = ImageBuffer::new(width, height);
+
+    for (x, y, pixel) in edge_detector.enumerate_pixels_mut() {
+      let is_edge = self.channels.iter()
+        .any(|channel| channel.get_pixel(x, y).data[0] == 0);
+
+      *pixel = if is_edge { Luma([0]) } else { Luma([255]) };
+    }
+
+    edge_detector
+  }
+
+  /// Counts the edge pixels of each orientation within a rectangle given by
+  /// its top left pixel and its size.
+  pub fn histogram(&self, x: u32, y: u32, width: u32, height: u32) -> [u32; ORIENTATIONS] {
+    let mut histogram = [0; ORIENTATIONS];
+
+    for (orientation, channel) in self.channels.iter().enumerate() {
+      let (channel_width, channel_height) = channel.dimensions();
+
+      for pixel_y in y..(y + height).min(channel_height) {
+        for pixel_x in x..(x + width).min(channel_width) {
+          if channel.get_pixel(pixel_x, pixel_y).data[0] == 0 {
+            histogram[orientation] += 1;
+          }
+        }
+      }
+    }
+
+    histogram
+  }
+
+}
+
+/// Finds edges in given grayscale picture by using a bank of oriented 3x3
+/// matrixes and merging their results into one image.
 pub fn find_edges(
   image: &DynamicImage
 ) -> GrayImage {
-  let image = smooth_out_polarized_pixels(&image);
+  find_oriented_edges(image).merged()
+}
 
-  let mut detectors: Vec<GrayImage> = Vec::new();
-  for matrix in FILTERS.iter() {
-    detectors.push(image.filter3x3(matrix).to_luma());
-  }
+/// Runs each of the oriented filters on the image and keeps its edges in
+/// a separate channel.
+pub fn find_oriented_edges(
+  image: &DynamicImage
+) -> OrientedEdges {
+  let image = smooth_out_polarized_pixels(image);
 
-  let (width, height) = detectors[0].dimensions();
-  let mut edge_detector = ImageBuffer::new(width, height);
+  let mut channels: Vec<GrayImage> = Vec::new();
+  for matrix in FILTERS.iter() {
+    let detector: GrayImage = image.filter3x3(matrix).to_luma();
+    let (width, height) = detector.dimensions();
+    let mut channel = ImageBuffer::new(width, height);
 
-  // Merges the two edge highlighters together into a single image.
-  for (x, y, pixel) in edge_detector.enumerate_pixels_mut() {
-    // Finds min and max values for a pixel in each detector.
-    let (max, min) = detectors.iter().fold((1, 1), |(max, min), detector| {
-      // Finds an edge value at given pixel.
+    for (x, y, pixel) in channel.enumerate_pixels_mut() {
       let edge_value = detector.get_pixel(x, y).data[0];
 
-      (edge_value.max(max), edge_value.min(min))
-    });
-
-    // If larger from both values equals max value (255 for white) or the lower
-    // equals the min value (0 for black), this pixel has been recognized as
-    // clear edge and will be coloured (therefore we do Luma([0]) for black).
-    // Otherwise the pixel value is white as the edge in this pixel was not
-    // that prevalent. We have to check for both max and min values (0 and 255)
-    // because the 3x3 kernels work in one direction. Should we only check for
-    // black, we would end up with edges where the darker colour was on
-    // top or right to the brighter one.
-    *pixel = if max == 255 || min == 0 {
-      Luma([0])
-    } else {
-      Luma([255])
-    };
+      // If the value equals max value (255 for white) or the min value (0 for
+      // black), this pixel has been recognized as clear edge and will be
+      // coloured (therefore we do Luma([0]) for black). Otherwise the pixel
+      // value is white as the edge in this pixel was not that prevalent. We
+      // have to check for both max and min values (0 and 255) because the 3x3
+      // kernels work in one direction. Should we only check for black, we
+      // would end up with edges where the darker colour was on top or right
+      // to the brighter one.
+      *pixel = if edge_value == 255 || edge_value == 0 {
+        Luma([0])
+      } else {
+        Luma([255])
+      };
+    }
+
+    channels.push(channel);
   }
 
-  edge_detector
+  OrientedEdges { channels }
 }
 
 /// Removes pixels that are too dark or bright so that the edge detection works
@@ -0,0 +1,180 @@
+//! Regression tests of the visual pipeline against golden outputs stored in
+//! `tests/golden/<case>`. Each case runs `identify_objects` on a fixture frame
+//! and compares the edge map, the heat map, the number of objects and their
+//! bounding boxes. The edges do not depend on the configuration, therefore all
+//! cases share `tests/golden/edges.png`, the heat map depends on the cell size
+//! and the heat source.
+//!
+//! Run `UPDATE_GOLDEN=1 cargo test golden` to write the current outputs as the
+//! new golden ones, so that changes to the algorithms show up in the diff.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use senses::media_source::{MediaKind, MediaSource};
+use senses::visual::image;
+use senses::visual::point::Point;
+use senses::visual::{identify_objects, HeatSource, SplittingStrategy, VisualConfig};
+
+/// Share of the pixels of the edge and heat maps that can differ from the
+/// golden ones.
+const MAP_TOLERANCE: f32 = 0.005_f32;
+
+/// Distance in cells that each side of a bounding box can move.
+const BOX_TOLERANCE: i64 = 1;
+
+/// Bounding box of a leaf object in cells, (left, top, right, bottom).
+type BoundingBox = (i64, i64, i64, i64);
+
+#[test]
+fn golden_default() {
+  check_golden("default", "doc/images/visual_input.png", VisualConfig::default());
+}
+
+#[test]
+fn golden_8px_cell() {
+  check_golden("8px_cell", "doc/images/visual_input.png", VisualConfig {
+    cell_size: 8,
+    ..VisualConfig::default()
+  });
+}
+
+/// Objects of the frame span fewer than `MAX_CELLS` cells of the default size,
+/// smaller cells make them large enough to be split.
+#[test]
+fn golden_watershed() {
+  check_golden("watershed", "doc/images/visual_input.png", VisualConfig {
+    cell_size: 4,
+    splitting: SplittingStrategy::Watershed,
+    ..VisualConfig::default()
+  });
+}
+
+#[test]
+fn golden_combined_heat() {
+  check_golden("combined_heat", "doc/images/visual_input.png", VisualConfig {
+    heat_source: HeatSource::Combined,
+    ..VisualConfig::default()
+  });
+}
+
+/// Runs the pipeline on the frame and either compares the outputs with the
+/// golden ones or overwrites them.
+fn check_golden(case: &str, frame: &str, config: VisualConfig) {
+  let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+  let goldens = root.join("tests").join("golden");
+  let golden = goldens.join(case);
+  let output = env::temp_dir().join(format!("golden-{}-{}", case, process::id()));
+  fs::create_dir_all(&output).unwrap();
+
+  let source = MediaSource::new(root.join(frame), MediaKind::Frame);
+  let mut objects = identify_objects(&source, &config, Some(&output)).unwrap();
+
+  let boxes: Vec<BoundingBox> = objects.iter_mut()
+    .flat_map(|object| object.leaves_mut())
+    .filter_map(|leaf| {
+      let reference = leaf.reference;
+
+      leaf.size().map(|(lower, higher)| bounding_box(reference, lower, higher))
+    })
+    .collect();
+
+  // Each map with the golden one it is compared to.
+  let maps = [("edges.png", goldens.join("edges.png")), ("heat.png", golden.join("heat.png"))];
+
+  if env::var("UPDATE_GOLDEN").is_ok() {
+    fs::create_dir_all(&golden).unwrap();
+    for (map, golden_map) in maps.iter() {
+      fs::copy(output.join(map), golden_map).unwrap();
+    }
+    fs::write(golden.join("objects.txt"), write_boxes(&boxes)).unwrap();
+    fs::remove_dir_all(&output).unwrap();
+    return;
+  }
+
+  let differences: Vec<f32> = maps.iter()
+    .map(|(map, golden_map)| compare_maps(&output.join(map), golden_map))
+    .collect();
+  fs::remove_dir_all(&output).unwrap();
+
+  for ((map, _), difference) in maps.iter().zip(differences.iter()) {
+    assert!(
+      *difference <= MAP_TOLERANCE,
+      "{}: {:.2}% of {} differs from the golden one",
+      case,
+      difference * 100_f32,
+      map,
+    );
+  }
+
+  let expected = read_boxes(&fs::read_to_string(golden.join("objects.txt")).unwrap());
+
+  assert_eq!(
+    boxes.len(),
+    expected.len(),
+    "{}: found {} objects instead of {}\nfound:\n{}",
+    case,
+    boxes.len(),
+    expected.len(),
+    write_boxes(&boxes),
+  );
+
+  for (index, (found, expected)) in boxes.iter().zip(expected.iter()).enumerate() {
+    let within = (found.0 - expected.0).abs() <= BOX_TOLERANCE &&
+      (found.1 - expected.1).abs() <= BOX_TOLERANCE &&
+      (found.2 - expected.2).abs() <= BOX_TOLERANCE &&
+      (found.3 - expected.3).abs() <= BOX_TOLERANCE;
+
+    assert!(within, "{}: object {} is at {:?} instead of {:?}", case, index, found, expected);
+  }
+}
+
+fn bounding_box(reference: Point, lower: Point, higher: Point) -> BoundingBox {
+  (
+    (reference.x + lower.x) as i64,
+    (reference.y + lower.y) as i64,
+    (reference.x + higher.x) as i64,
+    (reference.y + higher.y) as i64,
+  )
+}
+
+/// Share of pixels that differ between two maps. Maps of different dimensions
+/// differ completely.
+fn compare_maps(found: &PathBuf, golden: &PathBuf) -> f32 {
+  let found = image::open(found).unwrap().to_luma();
+  let golden = image::open(golden).unwrap().to_luma();
+
+  if found.dimensions() != golden.dimensions() {
+    return 1_f32;
+  }
+
+  let (width, height) = found.dimensions();
+  let different = found.pixels()
+    .zip(golden.pixels())
+    .filter(|(found, golden)| found.data[0] != golden.data[0])
+    .count();
+
+  different as f32 / (width * height) as f32
+}
+
+/// Writes one bounding box per line as `left,top,right,bottom`.
+fn write_boxes(boxes: &[BoundingBox]) -> String {
+  let mut text = String::new();
+
+  for (left, top, right, bottom) in boxes {
+    writeln!(text, "{},{},{},{}", left, top, right, bottom).unwrap();
+  }
+
+  text
+}
+
+fn read_boxes(text: &str) -> Vec<BoundingBox> {
+  text.lines().map(|line| {
+    let sides: Vec<i64> = line.split(',').map(|side| side.trim().parse().unwrap()).collect();
+
+    (sides[0], sides[1], sides[2], sides[3])
+  }).collect()
+}
@@ -1,68 +1,113 @@
-use senses::visual::image::GrayImage;
-use senses::visual::helpers::pixel_value;
+use senses::visual::point::Point;
+use senses::visual::image::{GrayImage, Luma};
+use senses::visual::helpers::{heat_statistics, pixel_value};
 
 /// Cell is a square that represents size*size pixels of the original image with
 /// a single number. It is used to track density of edges. The larger the cell
 /// size the lower the resolution of the heat map. The lower the cell size the
 /// less abstract the heat map becomes. It has to be a number that is divides
-/// both image width and image hight without a rest.
+/// both image width and image hight without a rest. This is the default size,
+/// it can be changed in the configuration.
 pub const CELL_SIZE: u32 = 10;
 
 type GrayImageRaw = Vec<Vec<u32>>;
 
-/// Transforms the bricked heat map where the cells are of CELL_SIZE to a more
-/// granular one where cells are CELL_SIZE / 2. This gives us better detail
+/// Transforms the bricked heat map where the cells are of cell_size to a more
+/// granular one where cells are cell_size / 2. This gives us better detail
 /// while preserving relationships between all parts of the image rather than
 /// cropping out a block and calculating the heat separately.
-pub fn heat_map(image: &GrayImage) -> (GrayImageRaw, u32, u32) {
+pub fn heat_map(image: &GrayImage, cell_size: u32) -> (GrayImageRaw, u32, u32) {
   let (width, height) = image.dimensions();
-  let bricked_heat_map: GrayImageRaw = bricked_heat_map(&image);
 
-  let mut heat_max: u32 = 1;
-  let mut heat_total: u32 = 0;
-  let mut heat_counter: u32 = 1;
+  // Counts number of black pixels (in the image the pixels are black and
+  // white only) in given cell.
+  let bricked_heat_map: GrayImageRaw = bricked_heat_map(width, height, cell_size, |x, y| {
+    if image.get_pixel(x, y).data[0] == 0 { 1_f32 } else { 0_f32 }
+  });
+
+  granular_heat_map(&bricked_heat_map, width, height, cell_size)
+}
+
+/// Creates a heat map of any two dimensional grid of intensities from 0 to 1,
+/// such as a spectrogram, in the same way as `heat_map` does for the edges of
+/// an image. Each value stands for a pixel and adds its intensity to the heat
+/// of the cells it belongs to. The grid has to be split into whole cells.
+pub fn intensity_heat_map(intensities: &[Vec<f32>], cell_size: u32) -> (GrayImageRaw, u32, u32) {
+  let height = intensities.len() as u32;
+  let width = intensities.first().map_or(0, |row| row.len()) as u32;
+
+  let bricked_heat_map: GrayImageRaw = bricked_heat_map(width, height, cell_size, |x, y| {
+    intensities[y as usize][x as usize].clamp(0_f32, 1_f32)
+  });
+
+  granular_heat_map(&bricked_heat_map, width, height, cell_size)
+}
+
+/// Averages the overlapping bricked cells into cells of half of their size.
+fn granular_heat_map(
+  bricked_heat_map: &GrayImageRaw,
+  width: u32,
+  height: u32,
+  cell_size: u32,
+) -> (GrayImageRaw, u32, u32) {
+
   let mut heat_map: GrayImageRaw = Vec::new();
 
-  for offset_y in 0..(2 * height / CELL_SIZE) {
+  for offset_y in 0..(2 * height / cell_size) {
     let mut row: Vec<u32> = vec!();
 
-    for offset_x in 0..(2 * width / CELL_SIZE) {
+    for offset_x in 0..(2 * width / cell_size) {
       // Sums the heat of all cells that participate to given offset and divides
       // it by 4. This will result in very low heat near the edges of the image.
       let heat: u32 = {
         let x: isize = offset_x as isize;
         let y: isize = offset_y as isize;
 
-        (pixel_value(&bricked_heat_map, x, y, 0) +
-        pixel_value(&bricked_heat_map, x, y - 1, 0) +
-        pixel_value(&bricked_heat_map, x - 1, y, 0) +
-        pixel_value(&bricked_heat_map, x - 1, y - 1, 0)) as u32
+        pixel_value(bricked_heat_map, x, y, 0) +
+        pixel_value(bricked_heat_map, x, y - 1, 0) +
+        pixel_value(bricked_heat_map, x - 1, y, 0) +
+        pixel_value(bricked_heat_map, x - 1, y - 1, 0)
       } / 4;
 
-      // Updates maximum observed heat.
-      heat_max = heat_max.max(heat);
-
-      // Adds info to heat average calculations.
-      if heat > 0 {
-        heat_total += heat;
-        heat_counter += 1;
-      }
-
       row.push(heat);
     }
 
     heat_map.push(row);
   }
 
-  (heat_map, heat_max, heat_total / heat_counter)
+  // Finds maximum observed heat and the average heat of warm cells.
+  let (heat_max, heat_mean) = heat_statistics(&heat_map);
+
+  (heat_map, heat_max, heat_mean)
+}
+
+/// Draws the heat map with a pixel per cell, so that it can be inspected. As
+/// with the edges, the background is white and the hottest cells are black.
+pub fn heat_map_image(heat_map: &[Vec<u32>], heat_max: u32) -> GrayImage {
+  let height = heat_map.len() as u32;
+  let width = heat_map.first().map_or(0, |row| row.len()) as u32;
+
+  GrayImage::from_fn(width, height, |x, y| {
+    let heat = heat_map[y as usize][x as usize] as u64 * 255 / heat_max.max(1) as u64;
+
+    Luma([255 - heat.min(255) as u8])
+  })
+}
+
+/// Returns the top left pixel of the block of cell_size / 2 pixels that given
+/// heat map cell stands for. The heat of the cell is an average of four
+/// overlaying bricked cells and this block is where all four of them overlap.
+pub fn cell_to_pixel(cell: Point, cell_size: u32) -> Point {
+  Point::new(cell.x * cell_size / 2, cell.y * cell_size / 2)
 }
 
 /// Calculates the heat map of overlaying cells. Most pixels therefore belong
 /// to 4 cells. Pixels on the edges of the image belong to 2 cells and pixels
-/// in the corners belong to one cell.
+/// in the corners belong to one cell. The heat of a cell is the sum of the
+/// heat of its pixels.
 ///
 /// In the following diagram, there are 4 cells where each cell is of the same
-/// size (e.g. cell 0x0 contains CELL_SIZE*CELL_SIZE pixels).
+/// size (e.g. cell 0x0 contains cell_size*cell_size pixels).
 /// a: row 0, col 0
 /// b: row 0, col 1
 /// c: row 1, col 0
@@ -73,15 +118,15 @@ pub fn heat_map(image: &GrayImage) -> (GrayImageRaw, u32, u32) {
 ///   |   ac   abcd   bd...
 /// 1 |   c... cd...  d...
 ///
-fn bricked_heat_map(image: &GrayImage) -> GrayImageRaw {
-  let (width, height) = image.dimensions();
+fn bricked_heat_map<F>(width: u32, height: u32, cell_size: u32, pixel_heat: F) -> GrayImageRaw
+  where F: Fn(u32, u32) -> f32 {
 
   // We want the cells to overlay one another by half of their size. Therefore
   // we can fit one full stack of cells plus one on top of it, but the second
-  // one starts with padding of CELL_SIZE / 2, therefore the overlay will fit
+  // one starts with padding of cell_size / 2, therefore the overlay will fit
   // one cell less.
-  let rows = (2 * height / CELL_SIZE) - 1;
-  let columns = (2 * width / CELL_SIZE) - 1;
+  let rows = (2 * height / cell_size) - 1;
+  let columns = (2 * width / cell_size) - 1;
 
   let mut heat_map: GrayImageRaw = Vec::new();
 
@@ -89,26 +134,20 @@ fn bricked_heat_map(image: &GrayImage) -> GrayImageRaw {
     let mut row: Vec<u32> = Vec::new();
 
     for offset_x in 0..columns {
-      let mut heat: u32 = 0;
+      let mut heat: f32 = 0_f32;
 
-      // Counts number of black pixels (in the image the pixels are black and
-      // white only) in given cell.
-      for cell_y in 0..CELL_SIZE {
-        for cell_x in 0..CELL_SIZE {
-          // Gets the value of the pixel on position that is padded by the
+      for cell_y in 0..cell_size {
+        for cell_x in 0..cell_size {
+          // Gets the heat of the pixel on position that is padded by the
           // offset plus the current cell index.
-          let pixel = image.get_pixel(
-            (offset_x * CELL_SIZE / 2) + cell_x,
-            (offset_y * CELL_SIZE / 2) + cell_y,
+          heat += pixel_heat(
+            (offset_x * cell_size / 2) + cell_x,
+            (offset_y * cell_size / 2) + cell_y,
           );
-
-          if pixel.data[0] == 0 {
-            heat += 1;
-          }
         }
       }
 
-      row.push(heat);
+      row.push(heat.round() as u32);
     }
 
     heat_map.push(row);
@@ -1,6 +1,6 @@
 /// Helper function for accessing values at given address in vector. If the
 /// address is out of bounds, it delivers the default value instead.
-pub fn pixel_value<T: Copy>(vec: &Vec<Vec<T>>, x: isize, y: isize, default: T) -> T {
+pub fn pixel_value<T: Copy>(vec: &[Vec<T>], x: isize, y: isize, default: T) -> T {
   if x < 0 || y < 0 {
     return default;
   }
@@ -22,3 +22,23 @@ pub fn to_point_map(input: Vec<Vec<u32>>) -> Vec<Vec<bool>> {
     |row| row.iter().map(|point| *point != 0).collect()
   ).collect()
 }
+
+/// Finds the maximum heat in the map and the mean heat of all cells that are
+/// not cold. The maximum is at least 1 so that the automaton always has a
+/// value to stabilize the cells at.
+pub fn heat_statistics(map: &[Vec<u32>]) -> (u32, u32) {
+  let mut heat_max: u32 = 1;
+  let mut heat_total: u32 = 0;
+  let mut heat_counter: u32 = 1;
+
+  for heat in map.iter().flat_map(|row| row.iter()) {
+    heat_max = heat_max.max(*heat);
+
+    if *heat > 0 {
+      heat_total += *heat;
+      heat_counter += 1;
+    }
+  }
+
+  (heat_max, heat_total / heat_counter)
+}
@@ -0,0 +1,95 @@
+//! Tests of the tree of objects that large highlights are split into.
+
+use senses::visual::config::SplittingStrategy;
+use senses::visual::extract_highlights::{extract_highlights, MAX_CELLS};
+use senses::visual::morphology::StructuringElement;
+use senses::visual::point::Point;
+use senses::visual::visual_object::VisualObject;
+
+type PointMap = Vec<Vec<bool>>;
+
+/// Map with given rectangles highlighted, each is its left, top, width and
+/// height.
+fn map(width: usize, height: usize, rectangles: &[(usize, usize, usize, usize)]) -> PointMap {
+  (0..height).map(|y| (0..width).map(|x| {
+    rectangles.iter().any(|(left, top, w, h)| x >= *left && x < left + w && y >= *top && y < top + h)
+  }).collect()).collect()
+}
+
+fn extract(map: PointMap, strategy: &SplittingStrategy) -> Vec<VisualObject> {
+  let mut objects: Vec<VisualObject> = Vec::new();
+  extract_highlights(map, Point::new(0, 0), strategy, &mut objects);
+
+  objects
+}
+
+fn span(object: &mut VisualObject) -> (u32, u32) {
+  let (lower, higher) = object.size().unwrap();
+
+  (higher.x - lower.x, higher.y - lower.y)
+}
+
+#[test]
+fn small_objects_are_not_split() {
+  let mut objects = extract(map(30, 30, &[(2, 2, 10, 10), (15, 15, 12, 8)]), &SplittingStrategy::Peel);
+
+  assert_eq!(objects.len(), 2);
+  for object in objects.iter_mut() {
+    assert!(object.children.is_empty());
+    assert_eq!(object.leaves().len(), 1);
+  }
+  assert_eq!((objects[0].points.len(), objects[1].points.len()), (100, 96));
+}
+
+#[test]
+fn split_object_keeps_its_parts_as_children() {
+  // Two blocks joined by a thin bridge are too wide together, opening cuts
+  // the bridge.
+  let bridged = map(64, 24, &[(2, 2, 25, 20), (27, 11, 10, 1), (37, 2, 25, 20)]);
+  let mut objects = extract(bridged, &SplittingStrategy::Open(StructuringElement::Square(1)));
+
+  assert_eq!(objects.len(), 1);
+  let parent = &mut objects[0];
+  assert!(span(parent).0 >= MAX_CELLS);
+  assert_eq!(parent.points.len(), 2 * 25 * 20 + 10);
+  assert_eq!(parent.children.len(), 2);
+
+  // Only the parts are leaves, each of them is an object of its own size.
+  let leaves = parent.leaves();
+  assert_eq!(leaves.len(), 2);
+  for (leaf, child) in leaves.iter().zip(parent.children.iter()) {
+    assert!(std::ptr::eq(*leaf, child));
+    assert!(leaf.children.is_empty());
+  }
+
+  for child in parent.children.iter_mut() {
+    let (width, height) = span(child);
+    assert!(width < MAX_CELLS && height < MAX_CELLS);
+  }
+}
+
+#[test]
+fn object_that_does_not_split_is_peeled() {
+  // Opening leaves a solid square as it is, it is peeled a layer at a time
+  // until it is small enough instead.
+  let mut objects = extract(map(50, 50, &[(2, 2, 44, 44)]), &SplittingStrategy::Open(StructuringElement::Square(1)));
+
+  assert_eq!(objects.len(), 1);
+  let leaves = objects[0].leaves_mut();
+  assert_eq!(leaves.len(), 1);
+
+  let leaf = leaves.into_iter().next().unwrap();
+  let (width, height) = span(leaf);
+  assert_eq!((width, height), (MAX_CELLS - 1, MAX_CELLS - 1));
+  assert_eq!(leaf.points.len(), (MAX_CELLS * MAX_CELLS) as usize);
+
+  // Each peeled layer is a generation of the tree.
+  let mut depth = 0;
+  let mut object = &objects[0];
+  while let Some(child) = object.children.first() {
+    assert_eq!(object.children.len(), 1);
+    object = child;
+    depth += 1;
+  }
+  assert_eq!(depth, 2);
+}
@@ -1,56 +1,165 @@
 extern crate image;
+extern crate rustfft;
 
 mod point;
+mod config;
+mod contour;
 mod helpers;
 mod heat_map;
 mod find_edges;
+mod saliency_map;
+mod watershed;
+mod morphology;
 mod visual_object;
 mod cellular_automaton;
 mod extract_highlights;
 mod cut_highlights_from_image;
 
-use senses::file::File;
-use senses::visual::point::Point;
-use senses::visual::heat_map::heat_map;
-use senses::visual::heat_map::CELL_SIZE;
-use senses::visual::find_edges::find_edges;
-use senses::visual::visual_object::VisualObject;
+#[cfg(test)]
+mod golden_tests;
+#[cfg(test)]
+mod property_tests;
+#[cfg(test)]
+mod morphology_tests;
+#[cfg(test)]
+mod watershed_tests;
+#[cfg(test)]
+mod contour_tests;
+#[cfg(test)]
+mod crop_tests;
+#[cfg(test)]
+mod saliency_tests;
+#[cfg(test)]
+mod orientation_tests;
+#[cfg(test)]
+mod highlights_tests;
+
+use std::path::Path;
+
+use senses::error::SensesError;
+use senses::media_source::{MediaKind, MediaSource};
+pub use senses::visual::point::Point;
+use senses::visual::heat_map::{heat_map, heat_map_image};
+pub use senses::visual::heat_map::{cell_to_pixel, intensity_heat_map};
+use senses::visual::find_edges::{find_edges, find_oriented_edges};
+pub use senses::visual::heat_map::CELL_SIZE;
+pub use senses::visual::config::{CropMode, HeatSource, SplittingStrategy, VisualConfig};
+pub use senses::visual::morphology::StructuringElement;
+use senses::visual::saliency_map::{saliency_map, weigh_heat_map};
+pub use senses::visual::visual_object::VisualObject;
+use senses::visual::visual_object::save_objects;
 use senses::visual::extract_highlights::extract_highlights;
 use senses::visual::cellular_automaton::cellular_automaton;
 use senses::visual::cut_highlights_from_image::cut_highlights_from_image;
 
-use self::image::{ImageBuffer, Rgb, RgbImage};
+use self::image::GenericImageView;
+
+/// Finds objects in the frame. Should an output directory be given, the edges,
+/// the heat map, a crop of each of the objects and a CSV with their shapes are
+/// saved into it. Returns the tree of objects where large objects hold the
+/// parts they were split into.
+pub fn identify_objects(
+  source: &MediaSource,
+  config: &VisualConfig,
+  output: Option<&Path>,
+) -> Result<Vec<VisualObject>, SensesError> {
+  if source.kind() != MediaKind::Frame {
+    return Err(SensesError::UnsupportedMedia(source.path().to_path_buf()));
+  }
+
+  let image = image::open(source.path())?;
 
-pub fn identify_objects(file: File) {
-  let image = image::open(file.full_path())
-    .expect("Could not open image.");
+  // The image has to be split into whole cells.
+  let cell_size = config.cell_size;
+  let (width, height) = image.dimensions();
+  if width % cell_size != 0 || height % cell_size != 0 || width < cell_size || height < cell_size {
+    return Err(SensesError::InvalidDimensions { width, height, cell_size });
+  }
 
   // Converts the image to grayscale and finds edges within the picture. Works
   // only with bright images. Resulting image has white background with dark
   // edges highlighted.
-  let edge_detector = find_edges(&image);
+  // Should the orientation of edges be kept, each filter of the bank outputs
+  // its own channel which are merged afterwards.
+  let oriented_edges = if config.edge_orientations {
+    Some(find_oriented_edges(&image))
+  } else {
+    None
+  };
+  let edge_detector = match oriented_edges {
+    Some(ref edges) => edges.merged(),
+    None => find_edges(&image),
+  };
 
-  edge_detector.save("output/test/edges.png").unwrap();
+  if let Some(output) = output {
+    edge_detector.save(output.join("edges.png"))?;
+  }
 
   // From the bricked heat map creates more detailed one where each cell is half
   // of the size of those in the bricked heat map. This multi-dimensional vector
   // represents density of edges in the original image.
   // Also returns maximum heat observed in the map and an average heat. This is
   // used for calculating the rules of the cellular automaton.
-  let (heat_map, heat_max, heat_mean) = heat_map(&edge_detector);
+  // Depending on the configuration, the edge heat is replaced by or weighed
+  // with the spectral residual saliency of the image.
+  let (heat_map, heat_max, heat_mean) = match config.heat_source {
+    HeatSource::Edges => heat_map(&edge_detector, cell_size),
+    HeatSource::Saliency => saliency_map(&image, cell_size),
+    HeatSource::Combined => {
+      let (edge_heat, _, _) = heat_map(&edge_detector, cell_size);
+      let (saliency, _, _) = saliency_map(&image, cell_size);
+
+      weigh_heat_map(&edge_heat, &saliency, cell_size)
+    },
+  };
+
+  if let Some(output) = output {
+    heat_map_image(&heat_map, heat_max).save(output.join("heat.png"))?;
+  }
 
+  let mut highlights = find_highlights(heat_map, heat_max, heat_mean, &config.splitting);
+
+  // Each highlight carries a histogram of its edge orientations.
+  if let Some(ref edges) = oriented_edges {
+    for highlight in highlights.iter_mut() {
+      highlight.visit_mut(&mut |object: &mut VisualObject| {
+        object.orientations = Some(object.orientation_histogram(edges, cell_size));
+      });
+    }
+  }
+
+  if let Some(output) = output {
+    for (i, highlight) in cut_highlights_from_image(&mut highlights, image, &config.crop, cell_size).iter().enumerate() {
+      highlight.save(output.join(format!("highlight_{}.png", i)))?;
+    }
+
+    save_objects(&mut highlights, output.join("objects.csv"))?;
+  }
+
+  Ok(highlights)
+}
+
+/// Finds objects in a heat map of any two dimensional grid, be it the edges of
+/// a frame or a spectrogram. The automaton stabilizes each cell into one of
+/// two states and the highlighted cells are then grouped into objects which
+/// are split with given strategy if they are too large.
+pub fn find_highlights(
+  heat_map: Vec<Vec<u32>>,
+  heat_max: u32,
+  heat_mean: u32,
+  splitting: &SplittingStrategy,
+) -> Vec<VisualObject> {
   // Stabilizes each cell into one of two states.
   let point_map = cellular_automaton(heat_map, heat_max, heat_mean);
 
-  // Finds objects using a recursive flood fill method.
+  // Finds objects using a flood fill method.
   let mut highlights: Vec<VisualObject> = Vec::new();
   extract_highlights(
     point_map,
     Point::new(0, 0),
+    splitting,
     &mut highlights,
   );
 
-  for (i, highlight) in cut_highlights_from_image(highlights, image).iter().enumerate() {
-    highlight.save("output/test/highlight_".to_owned() + &i.to_string() + ".png").unwrap();
-  }
+  highlights
 }
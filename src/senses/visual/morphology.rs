@@ -0,0 +1,254 @@
+use std::str::FromStr;
+use senses::visual::helpers::pixel_value;
+
+type PointMap = Vec<Vec<bool>>;
+
+/// Shape of the neighbourhood that morphological operations consider around
+/// each point. The numbers are radii of the shapes.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StructuringElement {
+
+  /// Square with sides of 2 * radius + 1 points. Radius of 1 is the Moore
+  /// neighbourhood.
+  Square(u32),
+
+  /// Cross with arms of radius points. Radius of 1 is the von Neumann
+  /// neighbourhood.
+  Cross(u32),
+
+  /// All points within euclidean distance of radius.
+  Disk(u32),
+
+  /// Arbitrary offsets (x, y) from the origin point.
+  Custom(Vec<(isize, isize)>),
+
+}
+
+impl StructuringElement {
+
+  /// Lists the offsets from the origin point that the element covers.
+  pub fn offsets(&self) -> Vec<(isize, isize)> {
+    let square = |radius: u32| {
+      let radius = radius as isize;
+
+      (-radius..(radius + 1)).flat_map(
+        move |y| (-radius..(radius + 1)).map(move |x| (x, y))
+      )
+    };
+
+    match self {
+      StructuringElement::Square(radius) => square(*radius).collect(),
+      StructuringElement::Cross(radius) => square(*radius)
+        .filter(|(x, y)| *x == 0 || *y == 0)
+        .collect(),
+      StructuringElement::Disk(radius) => square(*radius)
+        .filter(|(x, y)| x * x + y * y <= (*radius * *radius) as isize)
+        .collect(),
+      StructuringElement::Custom(offsets) => offsets.clone(),
+    }
+  }
+
+}
+
+impl FromStr for StructuringElement {
+
+  type Err = String;
+
+  /// Parses elements written as shape and radius, e.g. `disk:2`, or as custom
+  /// offsets separated by slashes, e.g. `custom:0,0/1,0/0,1`.
+  fn from_str(element: &str) -> Result<StructuringElement, String> {
+    let mut parts = element.splitn(2, ':');
+    let shape = parts.next().unwrap_or_default();
+    let parameter = parts.next();
+
+    if shape == "custom" {
+      return parameter
+        .unwrap_or_default()
+        .split('/')
+        .map(parse_offset)
+        .collect::<Option<Vec<(isize, isize)>>>()
+        .map(StructuringElement::Custom)
+        .ok_or_else(|| format!("Invalid offsets of structuring element {}", element));
+    }
+
+    let radius: u32 = parameter
+      .unwrap_or("1")
+      .parse()
+      .map_err(|_| format!("Invalid radius of structuring element {}", element))?;
+
+    match shape {
+      "square" => Ok(StructuringElement::Square(radius)),
+      "cross" => Ok(StructuringElement::Cross(radius)),
+      "disk" => Ok(StructuringElement::Disk(radius)),
+      _ => Err(format!("Unknown structuring element {}", element)),
+    }
+  }
+
+}
+
+/// Parses an offset written as `x,y`.
+fn parse_offset(offset: &str) -> Option<(isize, isize)> {
+  let mut coordinates = offset.splitn(2, ',');
+  let x = coordinates.next()?.trim().parse().ok()?;
+  let y = coordinates.next()?.trim().parse().ok()?;
+
+  Some((x, y))
+}
+
+/// Keeps only the points whose whole neighbourhood given by the element is
+/// highlighted. Points outside of the map are not highlighted, therefore the
+/// border of an object is always peeled away.
+pub fn erode(map: &PointMap, element: &StructuringElement) -> PointMap {
+  let offsets = element.offsets();
+
+  transform(map, |x, y| offsets.iter().all(
+    |(offset_x, offset_y)| pixel_value(map, x + offset_x, y + offset_y, false)
+  ))
+}
+
+/// Highlights every point that has a highlighted point within its
+/// neighbourhood given by the reflected element.
+pub fn dilate(map: &PointMap, element: &StructuringElement) -> PointMap {
+  let offsets = element.offsets();
+
+  transform(map, |x, y| offsets.iter().any(
+    |(offset_x, offset_y)| pixel_value(map, x - offset_x, y - offset_y, false)
+  ))
+}
+
+/// Erosion followed by dilation. Removes thin connections and small objects
+/// while keeping the size of the rest of the map.
+pub fn open(map: &PointMap, element: &StructuringElement) -> PointMap {
+  dilate(&erode(map, element), element)
+}
+
+/// Dilation followed by erosion. Fills small holes and gaps in objects.
+// Part of the set of operators, but no splitting strategy fills holes.
+#[allow(dead_code)]
+pub fn close(map: &PointMap, element: &StructuringElement) -> PointMap {
+  erode(&dilate(map, element), element)
+}
+
+/// Thins the objects to one point wide lines which preserve their topology,
+/// using Zhang-Suen algorithm. Each iteration removes border points in two
+/// sub-steps (south-east and north-west borders) until nothing changes.
+// Part of the set of operators, but no splitting strategy thins objects.
+#[allow(dead_code)]
+pub fn skeletonize(map: &PointMap) -> PointMap {
+  let mut skeleton: PointMap = map.clone();
+
+  loop {
+    let mut changed = false;
+
+    for step in 0..2 {
+      let mut removed: Vec<(usize, usize)> = Vec::new();
+
+      for (y, row) in skeleton.iter().enumerate() {
+        for (x, highlighted) in row.iter().enumerate() {
+          if *highlighted && is_removable(&skeleton, x as isize, y as isize, step) {
+            removed.push((x, y));
+          }
+        }
+      }
+
+      changed = changed || !removed.is_empty();
+
+      for (x, y) in removed {
+        skeleton[y][x] = false;
+      }
+    }
+
+    if !changed {
+      break;
+    }
+  }
+
+  skeleton
+}
+
+/// Calculates for each highlighted point the distance to the closest point that
+/// is not highlighted. Points outside of the map are not highlighted. Uses two
+/// pass chamfer algorithm where diagonal steps cost square root of 2, which
+/// approximates the euclidean distance.
+pub fn distance_transform(map: &PointMap) -> Vec<Vec<f32>> {
+  let diagonal = 2_f32.sqrt();
+  let mut distances: Vec<Vec<f32>> = map.iter().map(
+    |row| row.iter().map(
+      |highlighted| if *highlighted { f32::INFINITY } else { 0_f32 }
+    ).collect()
+  ).collect();
+
+  let height = distances.len() as isize;
+  let width = if height > 0 { distances[0].len() as isize } else { 0 };
+
+  // Neighbours that were already visited in the forward pass. The backward
+  // pass uses the same neighbours mirrored.
+  let forward = [(-1, 0, 1_f32), (-1, -1, diagonal), (0, -1, 1_f32), (1, -1, diagonal)];
+
+  let relax = |distances: &mut Vec<Vec<f32>>, x: isize, y: isize, sign: isize| {
+    for (offset_x, offset_y, cost) in forward.iter() {
+      let neighbour = pixel_value(distances, x + sign * offset_x, y + sign * offset_y, 0_f32);
+      let current = distances[y as usize][x as usize];
+
+      distances[y as usize][x as usize] = current.min(neighbour + cost);
+    }
+  };
+
+  for y in 0..height {
+    for x in 0..width {
+      relax(&mut distances, x, y, 1);
+    }
+  }
+
+  for y in (0..height).rev() {
+    for x in (0..width).rev() {
+      relax(&mut distances, x, y, -1);
+    }
+  }
+
+  distances
+}
+
+/// Counts highlighted points in the map.
+pub fn count_highlighted(map: &PointMap) -> usize {
+  map.iter()
+    .map(|row| row.iter().filter(|highlighted| **highlighted).count())
+    .sum()
+}
+
+/// Builds a new map of the same dimensions where each point is decided by
+/// given rule.
+fn transform<F>(map: &PointMap, rule: F) -> PointMap
+  where F: Fn(isize, isize) -> bool {
+  map.iter().enumerate().map(
+    |(y, row)| (0..row.len()).map(|x| rule(x as isize, y as isize)).collect()
+  ).collect()
+}
+
+/// Decides whether a point can be removed in given Zhang-Suen sub-step.
+fn is_removable(map: &PointMap, x: isize, y: isize, step: usize) -> bool {
+  // Neighbours clockwise starting from the one above the point.
+  let neighbours: Vec<bool> = [
+    (0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1),
+  ].iter().map(
+    |(offset_x, offset_y)| pixel_value(map, x + offset_x, y + offset_y, false)
+  ).collect();
+
+  // Number of highlighted neighbours.
+  let highlighted = neighbours.iter().filter(|neighbour| **neighbour).count();
+  // Number of transitions from off to on going around the neighbourhood.
+  let transitions = (0..8)
+    .filter(|index| !neighbours[*index] && neighbours[(index + 1) % 8])
+    .count();
+
+  let (north, east, south, west) = (neighbours[0], neighbours[2], neighbours[4], neighbours[6]);
+  // North, east and south, or east, south and west are not all highlighted
+  // in the first sub-step, the second one is turned around.
+  let removes_border = if step == 0 {
+    !(east && south && (north || west))
+  } else {
+    !(north && west && (east || south))
+  };
+
+  (2..=6).contains(&highlighted) && transitions == 1 && removes_border
+}
@@ -0,0 +1,157 @@
+//! Tests of the morphological operators on small hand drawn point maps, where
+//! `#` is a highlighted point and `.` is not.
+
+use senses::visual::morphology::{
+  close,
+  count_highlighted,
+  dilate,
+  erode,
+  open,
+  skeletonize,
+  StructuringElement,
+};
+
+type PointMap = Vec<Vec<bool>>;
+
+fn map(rows: &[&str]) -> PointMap {
+  rows.iter().map(|row| row.chars().map(|point| point == '#').collect()).collect()
+}
+
+#[test]
+fn erosion_peels_the_border() {
+  let eroded = erode(&map(&[
+    "#####",
+    "#####",
+    "#####",
+    "#####",
+  ]), &StructuringElement::Square(1));
+
+  assert_eq!(eroded, map(&[
+    ".....",
+    ".###.",
+    ".###.",
+    ".....",
+  ]));
+}
+
+#[test]
+fn dilation_draws_the_element_around_each_point() {
+  let point = map(&[
+    ".....",
+    ".....",
+    "..#..",
+    ".....",
+    ".....",
+  ]);
+
+  assert_eq!(dilate(&point, &StructuringElement::Cross(1)), map(&[
+    ".....",
+    "..#..",
+    ".###.",
+    "..#..",
+    ".....",
+  ]));
+
+  // The element is reflected, an offset to the right moves the point right.
+  assert_eq!(dilate(&point, &StructuringElement::Custom(vec!((1, 0)))), map(&[
+    ".....",
+    ".....",
+    "...#.",
+    ".....",
+    ".....",
+  ]));
+}
+
+#[test]
+fn opening_cuts_thin_bridges_and_keeps_the_rest() {
+  let bridged = map(&[
+    "...........",
+    ".###...###.",
+    ".#########.",
+    ".###...###.",
+    "...........",
+  ]);
+
+  assert_eq!(open(&bridged, &StructuringElement::Square(1)), map(&[
+    "...........",
+    ".###...###.",
+    ".###...###.",
+    ".###...###.",
+    "...........",
+  ]));
+}
+
+#[test]
+fn closing_fills_holes() {
+  let holed = map(&[
+    ".......",
+    ".#####.",
+    ".#####.",
+    ".##.##.",
+    ".#####.",
+    ".#####.",
+    ".......",
+  ]);
+
+  assert_eq!(close(&holed, &StructuringElement::Square(1)), map(&[
+    ".......",
+    ".#####.",
+    ".#####.",
+    ".#####.",
+    ".#####.",
+    ".#####.",
+    ".......",
+  ]));
+}
+
+#[test]
+fn skeleton_of_a_bar_is_a_line() {
+  let bar = map(&[
+    "...........",
+    ".#########.",
+    ".#########.",
+    ".#########.",
+    "...........",
+  ]);
+  let skeleton = skeletonize(&bar);
+
+  // Thinning never adds points and leaves at most one point per column.
+  for (row, skeleton_row) in bar.iter().zip(skeleton.iter()) {
+    for (point, skeleton_point) in row.iter().zip(skeleton_row.iter()) {
+      assert!(!*skeleton_point || *point);
+    }
+  }
+
+  for x in 0..11 {
+    assert!(skeleton.iter().filter(|row| row[x]).count() <= 1, "column {} is thick", x);
+  }
+
+  // The middle of the bar survives as a line in the middle row.
+  assert!((3..8).all(|x| skeleton[2][x]));
+  assert!(count_highlighted(&skeleton) < count_highlighted(&bar));
+}
+
+#[test]
+fn skeleton_keeps_lines() {
+  let line = map(&[
+    ".......",
+    ".#####.",
+    ".......",
+  ]);
+
+  assert_eq!(skeletonize(&line), line);
+}
+
+#[test]
+fn elements_are_parsed_from_shape_and_radius_or_offsets() {
+  assert_eq!("disk:2".parse(), Ok(StructuringElement::Disk(2)));
+  assert_eq!("cross".parse(), Ok(StructuringElement::Cross(1)));
+  assert_eq!(
+    "custom:0,0/1,0/0,-1".parse(),
+    Ok(StructuringElement::Custom(vec!((0, 0), (1, 0), (0, -1)))),
+  );
+
+  assert!("custom:".parse::<StructuringElement>().is_err());
+  assert!("custom:1".parse::<StructuringElement>().is_err());
+  assert!("star:1".parse::<StructuringElement>().is_err());
+}
@@ -0,0 +1,84 @@
+//! Tests of the oriented edges on frames of only horizontal or only vertical
+//! lines.
+
+use senses::visual::find_edges::{find_oriented_edges, OrientedEdges, ORIENTATIONS};
+use senses::visual::image::{ImageRgb8, Rgb, RgbImage};
+use senses::visual::point::Point;
+use senses::visual::visual_object::VisualObject;
+
+const SIZE: u32 = 60;
+
+/// Frame of dark lines every 10 pixels, horizontal or vertical.
+fn lines(horizontal: bool) -> OrientedEdges {
+  let frame = RgbImage::from_fn(SIZE, SIZE, |x, y| {
+    let position = if horizontal { y } else { x };
+
+    if position % 10 < 2 { Rgb([0, 0, 0]) } else { Rgb([200, 200, 200]) }
+  });
+
+  find_oriented_edges(&ImageRgb8(frame))
+}
+
+const CELL_SIZE: u32 = 10;
+
+/// Object of all cells of a square of pixels given by its top left pixel and
+/// size, both multiples of half of the cell size.
+fn square(left: u32, top: u32, size: u32) -> VisualObject {
+  let step = CELL_SIZE / 2;
+  let mut object = VisualObject::new(Point::new(left / step, top / step));
+
+  for y in 0..(size / step) {
+    for x in 0..(size / step) {
+      object.push(Point::new(x, y));
+    }
+  }
+
+  object
+}
+
+#[test]
+fn lines_light_up_the_filter_of_their_orientation() {
+  // First filter of the bank highlights horizontal edges, second one
+  // vertical edges, the other three respond to both.
+  let horizontal = lines(true).histogram(0, 0, SIZE, SIZE);
+  let vertical = lines(false).histogram(0, 0, SIZE, SIZE);
+
+  assert!(horizontal[0] > 5 * horizontal[1], "{:?}", horizontal);
+  assert!(vertical[1] > 5 * vertical[0], "{:?}", vertical);
+
+  // Lines turned by a right angle swap the two channels.
+  assert_eq!((horizontal[0], horizontal[1]), (vertical[1], vertical[0]));
+}
+
+#[test]
+fn merged_edges_are_the_union_of_the_channels() {
+  let edges = lines(true);
+  let merged = edges.merged();
+
+  for (x, y, pixel) in merged.enumerate_pixels() {
+    let in_any = edges.channels.iter().any(|channel| channel.get_pixel(x, y).data[0] == 0);
+
+    assert_eq!(pixel.data[0] == 0, in_any, "pixel {}, {}", x, y);
+  }
+
+  let histogram = edges.histogram(0, 0, SIZE, SIZE);
+  let merged_count = merged.pixels().filter(|pixel| pixel.data[0] == 0).count() as u32;
+  assert!(merged_count >= *histogram.iter().max().unwrap());
+  assert!(merged_count <= histogram.iter().sum());
+}
+
+#[test]
+fn object_histogram_counts_the_edges_under_its_cells() {
+  for horizontal in [true, false].iter() {
+    let edges = lines(*horizontal);
+
+    let whole: [u32; ORIENTATIONS] = square(0, 0, SIZE).orientation_histogram(&edges, CELL_SIZE);
+    assert_eq!(whole, edges.histogram(0, 0, SIZE, SIZE));
+
+    let part = square(20, 10, 30).orientation_histogram(&edges, CELL_SIZE);
+    assert_eq!(part, edges.histogram(20, 10, 30, 30));
+
+    let (along, across) = if *horizontal { (0, 1) } else { (1, 0) };
+    assert!(part[along] > part[across], "{:?}", part);
+  }
+}
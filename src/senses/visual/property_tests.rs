@@ -0,0 +1,152 @@
+//! Property tests of the flood fill, peeling and bounding boxes over randomly
+//! generated point maps.
+
+use proptest::prelude::*;
+
+use senses::visual::config::SplittingStrategy;
+use senses::visual::extract_highlights::{extract_highlights, find_highlights_in_map};
+use senses::visual::morphology::count_highlighted;
+use senses::visual::point::Point;
+use senses::visual::visual_object::VisualObject;
+
+type PointMap = Vec<Vec<bool>>;
+
+/// Maps of up to 24x24 cells where roughly every other cell is highlighted.
+fn point_map() -> impl Strategy<Value = PointMap> {
+  (1_usize..24, 1_usize..24).prop_flat_map(|(width, height)| {
+    prop::collection::vec(prop::collection::vec(any::<bool>(), width), height)
+  })
+}
+
+/// Large maps that are mostly highlighted, so that the objects in them have to
+/// be split.
+fn dense_point_map() -> impl Strategy<Value = PointMap> {
+  (40_usize..64, 40_usize..64).prop_flat_map(|(width, height)| {
+    prop::collection::vec(prop::collection::vec(prop::bool::weighted(0.9), width), height)
+  })
+}
+
+fn splitting_strategy() -> impl Strategy<Value = SplittingStrategy> {
+  prop_oneof![
+    Just(SplittingStrategy::Peel),
+    Just(SplittingStrategy::Watershed),
+  ]
+}
+
+fn is_neighbour(a: Point, b: Point) -> bool {
+  (a.x as i64 - b.x as i64).abs() <= 1 && (a.y as i64 - b.y as i64).abs() <= 1
+}
+
+/// Marks the points of the object, including its reference, in an empty map
+/// of given dimensions.
+fn object_map(object: &VisualObject, width: usize, height: usize) -> PointMap {
+  let mut map: PointMap = vec!(vec!(false; width); height);
+
+  for point in object.points.iter() {
+    let point = *point + object.reference;
+    map[point.y as usize][point.x as usize] = true;
+  }
+
+  map
+}
+
+proptest! {
+
+  #[test]
+  fn components_partition_highlighted_cells(map in point_map()) {
+    let (width, height) = (map[0].len(), map.len());
+    let objects = find_highlights_in_map(map.clone(), Point::new(0, 0));
+
+    // Every highlighted cell belongs to exactly one object and no object has a
+    // cell that is not highlighted.
+    let mut owners: Vec<Vec<usize>> = vec!(vec!(0; width); height);
+    for object in objects.iter() {
+      for point in object.points.iter() {
+        prop_assert!(map[point.y as usize][point.x as usize]);
+        owners[point.y as usize][point.x as usize] += 1;
+      }
+    }
+
+    for y in 0..height {
+      for x in 0..width {
+        prop_assert_eq!(owners[y][x], if map[y][x] { 1 } else { 0 }, "cell {}x{}", x, y);
+      }
+    }
+
+    // Objects are whole components, therefore no two of them touch.
+    for (index, object) in objects.iter().enumerate() {
+      for other in objects.iter().skip(index + 1) {
+        let touches = object.points.iter()
+          .any(|a| other.points.iter().any(|b| is_neighbour(*a, *b)));
+
+        prop_assert!(!touches);
+      }
+    }
+  }
+
+  #[test]
+  fn size_bounds_every_point(map in point_map()) {
+    for mut object in find_highlights_in_map(map, Point::new(0, 0)) {
+      let (lower, higher) = object.size().unwrap();
+
+      for point in object.points.iter() {
+        prop_assert!(lower.x <= point.x && point.x <= higher.x);
+        prop_assert!(lower.y <= point.y && point.y <= higher.y);
+      }
+
+      // The box is the smallest one, each of its sides touches a point.
+      prop_assert!(object.points.iter().any(|point| point.x == lower.x));
+      prop_assert!(object.points.iter().any(|point| point.y == lower.y));
+      prop_assert!(object.points.iter().any(|point| point.x == higher.x));
+      prop_assert!(object.points.iter().any(|point| point.y == higher.y));
+    }
+  }
+
+  #[test]
+  fn peeling_never_adds_cells(map in point_map()) {
+    for mut object in find_highlights_in_map(map, Point::new(0, 0)) {
+      let map = object.point_map().unwrap();
+      let peeled = object.peeled_map().unwrap();
+
+      prop_assert_eq!(peeled.len(), map.len());
+
+      for (row, peeled_row) in map.iter().zip(peeled.iter()) {
+        prop_assert_eq!(peeled_row.len(), row.len());
+
+        for (cell, peeled_cell) in row.iter().zip(peeled_row.iter()) {
+          prop_assert!(!*peeled_cell || *cell);
+        }
+      }
+
+      prop_assert!(count_highlighted(&peeled) < count_highlighted(&map));
+    }
+  }
+
+  #[test]
+  fn split_objects_stay_within_the_map(
+    map in dense_point_map(),
+    strategy in splitting_strategy(),
+  ) {
+    let (width, height) = (map[0].len(), map.len());
+    let mut objects: Vec<VisualObject> = Vec::new();
+    extract_highlights(map.clone(), Point::new(0, 0), &strategy, &mut objects);
+
+    // Leaves of the tree never cover a cell that was not highlighted, nor do
+    // two of them share a cell.
+    let mut covered: PointMap = vec!(vec!(false; width); height);
+    for leaf in objects.iter().flat_map(|object| object.leaves()) {
+      let leaf_map = object_map(leaf, width, height);
+
+      for y in 0..height {
+        for x in 0..width {
+          if leaf_map[y][x] {
+            prop_assert!(map[y][x]);
+            prop_assert!(!covered[y][x]);
+            covered[y][x] = true;
+          }
+        }
+      }
+    }
+  }
+
+}
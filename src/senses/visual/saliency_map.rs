@@ -0,0 +1,239 @@
+use senses::visual::image::{DynamicImage, FilterType, GenericImageView};
+use senses::visual::rustfft::FftPlanner;
+use senses::visual::rustfft::num_complex::Complex;
+use senses::visual::helpers::{heat_statistics, pixel_value};
+
+/// Width of the image the spectral residual is calculated on. The residual
+/// describes the gist of the scene rather than its detail, therefore the image
+/// is heavily downscaled. Height is derived from the image aspect ratio.
+const SALIENCY_WIDTH: u32 = 64;
+
+/// Standard deviation of the gaussian that smooths out the saliency map.
+const SALIENCY_SIGMA: f32 = 2.5_f32;
+
+type GrayImageRaw = Vec<Vec<u32>>;
+type FloatMap = Vec<Vec<f32>>;
+type ComplexMap = Vec<Vec<Complex<f32>>>;
+
+/// Builds a saliency map using the spectral residual approach. The log
+/// amplitude spectrum of natural images is smooth, therefore whatever sticks
+/// out of its local average is what makes the image special. Transforming the
+/// residual back with the original phase highlights those areas.
+///
+/// The result is resampled onto the same cell grid as the `heat_map` and its
+/// values are in the same range (0 to cell_size * cell_size), so that it can
+/// be used in place of the edge heat in front of the cellular automaton.
+pub fn saliency_map(image: &DynamicImage, cell_size: u32) -> (GrayImageRaw, u32, u32) {
+  let (width, height) = image.dimensions();
+  let scaled_height = (SALIENCY_WIDTH * height / width).max(1);
+
+  let small = image
+    .grayscale()
+    .resize_exact(SALIENCY_WIDTH, scaled_height, FilterType::Triangle)
+    .to_luma();
+
+  let mut spectrum: ComplexMap = (0..scaled_height).map(
+    |y| (0..SALIENCY_WIDTH).map(
+      |x| Complex::new(small.get_pixel(x, y).data[0] as f32, 0_f32)
+    ).collect()
+  ).collect();
+
+  fft_2d(&mut spectrum, false);
+
+  let log_amplitude: FloatMap = spectrum.iter().map(
+    |row| row.iter().map(|value| (value.norm() + 1_f32).ln()).collect()
+  ).collect();
+
+  // Spectral residual is what remains after the local average of the log
+  // amplitude is subtracted. It is combined with the original phase.
+  let averaged = mean_filter(&log_amplitude);
+  for (y, row) in spectrum.iter_mut().enumerate() {
+    for (x, value) in row.iter_mut().enumerate() {
+      let residual = log_amplitude[y][x] - averaged[y][x];
+      *value = Complex::from_polar(residual.exp(), value.arg());
+    }
+  }
+
+  fft_2d(&mut spectrum, true);
+
+  let saliency: FloatMap = gaussian_blur(
+    &spectrum.iter().map(
+      |row| row.iter().map(|value| value.norm_sqr()).collect()
+    ).collect(),
+    SALIENCY_SIGMA,
+  );
+
+  let saliency_map = to_cell_grid(&saliency, width, height, cell_size);
+  let (saliency_max, saliency_mean) = heat_statistics(&saliency_map);
+
+  (saliency_map, saliency_max, saliency_mean)
+}
+
+/// Weighs the edge heat of each cell by its saliency. Cells that contain lots
+/// of edges but are not salient (e.g. texture of the background) cool down.
+pub fn weigh_heat_map(
+  heat_map: &GrayImageRaw,
+  saliency_map: &GrayImageRaw,
+  cell_size: u32,
+) -> (GrayImageRaw, u32, u32) {
+  let weighed_map: GrayImageRaw = heat_map.iter().enumerate().map(
+    |(y, row)| row.iter().enumerate().map(|(x, heat)| {
+      let saliency = pixel_value(saliency_map, x as isize, y as isize, 0);
+
+      heat * saliency / (cell_size * cell_size)
+    }).collect()
+  ).collect();
+
+  let (heat_max, heat_mean) = heat_statistics(&weighed_map);
+
+  (weighed_map, heat_max, heat_mean)
+}
+
+/// Samples the saliency in the middle of each heat map cell, scales it into
+/// the heat range and removes everything below the mean saliency, which is
+/// considered background.
+fn to_cell_grid(saliency: &FloatMap, width: u32, height: u32, cell_size: u32) -> GrayImageRaw {
+  let saliency_height = saliency.len() as f32;
+  let saliency_width = saliency[0].len() as f32;
+
+  let saliency_max = saliency.iter()
+    .flat_map(|row| row.iter())
+    .fold(0_f32, |max, value| max.max(*value));
+  let saliency_mean = saliency.iter()
+    .flat_map(|row| row.iter())
+    .sum::<f32>() / (saliency_width * saliency_height);
+
+  // Nothing stands out in a flat image.
+  if saliency_max <= saliency_mean {
+    return vec!(vec!(0; (2 * width / cell_size) as usize); (2 * height / cell_size) as usize);
+  }
+
+  let mut cell_grid: GrayImageRaw = Vec::new();
+
+  for offset_y in 0..(2 * height / cell_size) {
+    let mut row: Vec<u32> = Vec::new();
+
+    for offset_x in 0..(2 * width / cell_size) {
+      // Cell covers cell_size / 2 pixels in each direction, sample its centre.
+      let x = (offset_x as f32 + 0.5_f32) * (cell_size / 2) as f32 * saliency_width / width as f32;
+      let y = (offset_y as f32 + 0.5_f32) * (cell_size / 2) as f32 * saliency_height / height as f32;
+      let value = bilinear_sample(saliency, x - 0.5_f32, y - 0.5_f32);
+
+      row.push(if value < saliency_mean {
+        0
+      } else {
+        (value / saliency_max * (cell_size * cell_size) as f32).round() as u32
+      });
+    }
+
+    cell_grid.push(row);
+  }
+
+  cell_grid
+}
+
+/// Two dimensional FFT done as one dimensional FFTs of rows followed by
+/// columns. The inverse transform is normalized.
+fn fft_2d(map: &mut ComplexMap, inverse: bool) {
+  let height = map.len();
+  let width = map[0].len();
+
+  let mut planner = FftPlanner::new();
+  let (row_fft, column_fft) = if inverse {
+    (planner.plan_fft_inverse(width), planner.plan_fft_inverse(height))
+  } else {
+    (planner.plan_fft_forward(width), planner.plan_fft_forward(height))
+  };
+
+  for row in map.iter_mut() {
+    row_fft.process(row);
+  }
+
+  let mut column: Vec<Complex<f32>> = vec!(Complex::new(0_f32, 0_f32); height);
+  for x in 0..width {
+    for (value, row) in column.iter_mut().zip(map.iter()) {
+      *value = row[x];
+    }
+
+    column_fft.process(&mut column);
+
+    for (value, row) in column.iter().zip(map.iter_mut()) {
+      row[x] = *value;
+    }
+  }
+
+  if inverse {
+    let scale = (width * height) as f32;
+    for value in map.iter_mut().flat_map(|row| row.iter_mut()) {
+      *value /= scale;
+    }
+  }
+}
+
+/// Replaces each value with the average of its 3x3 neighbourhood. Values
+/// outside of the map are not counted.
+fn mean_filter(map: &FloatMap) -> FloatMap {
+  map.iter().enumerate().map(|(y, row)| row.iter().enumerate().map(|(x, _)| {
+    let mut total = 0_f32;
+    let mut count = 0_f32;
+
+    for neighbour_y in (y as isize - 1)..(y as isize + 2) {
+      for neighbour_x in (x as isize - 1)..(x as isize + 2) {
+        let value = pixel_value(map, neighbour_x, neighbour_y, -1_f32);
+
+        // Log amplitude is never negative, therefore -1 marks out of bounds.
+        if value >= 0_f32 {
+          total += value;
+          count += 1_f32;
+        }
+      }
+    }
+
+    total / count
+  }).collect()).collect()
+}
+
+/// Separable gaussian blur with edge values repeated outside of the map.
+fn gaussian_blur(map: &FloatMap, sigma: f32) -> FloatMap {
+  let radius = (sigma * 3_f32).ceil() as isize;
+  let kernel: Vec<f32> = (-radius..(radius + 1))
+    .map(|offset| (-(offset * offset) as f32 / (2_f32 * sigma * sigma)).exp())
+    .collect();
+  let kernel_total: f32 = kernel.iter().sum();
+
+  let height = map.len() as isize;
+  let width = map[0].len() as isize;
+  let clamp = |value: isize, max: isize| value.max(0).min(max - 1) as usize;
+
+  let horizontal: FloatMap = (0..height).map(|y| (0..width).map(|x| {
+    kernel.iter().enumerate().map(|(index, weight)| {
+      weight * map[y as usize][clamp(x + index as isize - radius, width)]
+    }).sum::<f32>() / kernel_total
+  }).collect()).collect();
+
+  (0..height).map(|y| (0..width).map(|x| {
+    kernel.iter().enumerate().map(|(index, weight)| {
+      weight * horizontal[clamp(y + index as isize - radius, height)][x as usize]
+    }).sum::<f32>() / kernel_total
+  }).collect()).collect()
+}
+
+/// Reads a value at a position between the map points by linearly
+/// interpolating its four closest neighbours.
+fn bilinear_sample(map: &FloatMap, x: f32, y: f32) -> f32 {
+  let max_x = (map[0].len() - 1) as f32;
+  let max_y = (map.len() - 1) as f32;
+  let x = x.max(0_f32).min(max_x);
+  let y = y.max(0_f32).min(max_y);
+
+  let (left, top) = (x.floor(), y.floor());
+  let (right, bottom) = (x.ceil(), y.ceil());
+  let (dx, dy) = (x - left, y - top);
+
+  let value = |x: f32, y: f32| map[y as usize][x as usize];
+
+  value(left, top) * (1_f32 - dx) * (1_f32 - dy) +
+  value(right, top) * dx * (1_f32 - dy) +
+  value(left, bottom) * (1_f32 - dx) * dy +
+  value(right, bottom) * dx * dy
+}
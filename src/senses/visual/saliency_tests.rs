@@ -0,0 +1,64 @@
+//! Tests of the spectral residual saliency on a blob that stands out of a
+//! periodic texture.
+
+use senses::visual::image::{DynamicImage, ImageRgb8, Rgb, RgbImage};
+use senses::visual::saliency_map::saliency_map;
+
+const CELL_SIZE: u32 = 10;
+
+/// Frame of vertical stripes with a dark square of 40 pixels at given position.
+fn frame(blob_x: u32, blob_y: u32) -> DynamicImage {
+  ImageRgb8(RgbImage::from_fn(320, 320, |x, y| {
+    let in_blob = x >= blob_x && x < blob_x + 40 && y >= blob_y && y < blob_y + 40;
+
+    if in_blob {
+      Rgb([0, 0, 0])
+    } else if (x / 10) % 2 == 0 {
+      Rgb([160, 160, 160])
+    } else {
+      Rgb([220, 220, 220])
+    }
+  }))
+}
+
+/// Mean saliency of the cells inside and outside of the square of pixels.
+fn inside_and_outside(map: &[Vec<u32>], left: u32, top: u32, size: u32) -> (f32, f32) {
+  let (mut inside, mut outside) = ((0_f32, 0_f32), (0_f32, 0_f32));
+
+  for (y, row) in map.iter().enumerate() {
+    for (x, value) in row.iter().enumerate() {
+      // Cell starts every half of the cell size, its centre decides.
+      let centre_x = x as u32 * CELL_SIZE / 2 + CELL_SIZE / 4;
+      let centre_y = y as u32 * CELL_SIZE / 2 + CELL_SIZE / 4;
+      let is_inside = centre_x >= left && centre_x < left + size && centre_y >= top && centre_y < top + size;
+      let total = if is_inside { &mut inside } else { &mut outside };
+
+      total.0 += *value as f32;
+      total.1 += 1_f32;
+    }
+  }
+
+  (inside.0 / inside.1, outside.0 / outside.1)
+}
+
+#[test]
+fn blob_is_hotter_than_the_texture_around_it() {
+  for (blob_x, blob_y) in [(60, 60), (200, 140)].iter() {
+    let (map, max, _) = saliency_map(&frame(*blob_x, *blob_y), CELL_SIZE);
+
+    assert_eq!((map.len(), map[0].len()), (64, 64));
+
+    let (inside, outside) = inside_and_outside(&map, *blob_x, *blob_y, 40);
+    assert!(inside > 2_f32 * outside, "{:?}: {} inside, {} outside", (blob_x, blob_y), inside, outside);
+
+    // The hottest cell is on the blob or right next to it.
+    let (hottest_x, hottest_y) = map.iter().enumerate()
+      .flat_map(|(y, row)| row.iter().enumerate().map(move |(x, value)| (x, y, *value)))
+      .find(|(_, _, value)| *value == max)
+      .map(|(x, y, _)| (x as u32 * CELL_SIZE / 2, y as u32 * CELL_SIZE / 2))
+      .unwrap();
+    assert!(hottest_x + 20 >= *blob_x && hottest_x < blob_x + 60, "hottest at {}", hottest_x);
+    assert!(hottest_y + 20 >= *blob_y && hottest_y < blob_y + 60, "hottest at {}", hottest_y);
+  }
+}
+
@@ -1,9 +1,25 @@
 use std::fmt;
+use std::fs;
+use std::path::Path;
+use senses::error::SensesError;
 use senses::visual::point::Point;
-use senses::visual::helpers::pixel_value;
+use senses::visual::morphology::{erode, StructuringElement};
+use senses::visual::contour::{
+  convex_hull,
+  min_area_rect,
+  RotatedRect,
+  simplify_polygon,
+  trace_contour,
+};
+use senses::visual::heat_map::cell_to_pixel;
+use senses::visual::find_edges::{OrientedEdges, ORIENTATIONS};
 
 type PointMap = Vec<Vec<bool>>;
 
+/// How many cells the polygon of an object can stray from its boundary.
+const POLYGON_EPSILON: f32 = 1_f32;
+
+#[derive(Clone)]
 pub struct VisualObject {
 
   /// Maps the point to the original picture.
@@ -16,6 +32,14 @@ pub struct VisualObject {
   /// Second point the right most point with the highest y value.
   size: Option<(Point, Point)>,
 
+  /// How many edge pixels of each orientation the object contains. Only
+  /// calculated if the pipeline keeps the orientation channels.
+  pub orientations: Option<[u32; ORIENTATIONS]>,
+
+  /// Objects this object was split into because it was too large. Their
+  /// reference is the top left point of this object.
+  pub children: Vec<VisualObject>,
+
 }
 
 impl VisualObject {
@@ -25,6 +49,8 @@ impl VisualObject {
     VisualObject {
       points: Vec::new(),
       size: None,
+      orientations: None,
+      children: Vec::new(),
       reference,
     }
   }
@@ -38,7 +64,7 @@ impl VisualObject {
   /// Returns a tuple with points defining the smallest encapsulating rectangle,
   /// meaning all points are within this rectangle.
   pub fn size(&mut self) -> Option<(Point, Point)> {
-    if self.points.len() == 0 {
+    if self.points.is_empty() {
       return None;
     }
 
@@ -74,14 +100,59 @@ impl VisualObject {
     self.size
   }
 
-  pub fn point_map(&mut self) -> Option<PointMap> {
-    let size = self.size();
+  /// Returns objects in this tree that were not split any further. An object
+  /// without children is a leaf itself.
+  pub fn leaves(&self) -> Vec<&VisualObject> {
+    if self.children.is_empty() {
+      return vec!(self);
+    }
 
-    if size.is_none() {
-      return None;
+    self.children.iter().flat_map(|child| child.leaves()).collect()
+  }
+
+  /// Returns mutable references to objects in this tree that were not split
+  /// any further.
+  pub fn leaves_mut(&mut self) -> Vec<&mut VisualObject> {
+    if self.children.is_empty() {
+      return vec!(self);
+    }
+
+    self.children.iter_mut().flat_map(|child| child.leaves_mut()).collect()
+  }
+
+  /// Calls the visitor with this object and then with each of its descendants.
+  pub fn visit_mut<F>(&mut self, visitor: &mut F)
+    where F: FnMut(&mut VisualObject) {
+    visitor(self);
+
+    for child in self.children.iter_mut() {
+      child.visit_mut(visitor);
     }
+  }
+
+  /// Counts edge pixels of each orientation within the cells of the object.
+  /// This is a cheap shape feature of the object.
+  pub fn orientation_histogram(
+    &self,
+    edges: &OrientedEdges,
+    cell_size: u32,
+  ) -> [u32; ORIENTATIONS] {
+    let mut histogram = [0; ORIENTATIONS];
+
+    for point in self.points.iter() {
+      let pixel = cell_to_pixel(*point + self.reference, cell_size);
+      let cell_histogram = edges.histogram(pixel.x, pixel.y, cell_size / 2, cell_size / 2);
+
+      for (total, count) in histogram.iter_mut().zip(cell_histogram.iter()) {
+        *total += count;
+      }
+    }
+
+    histogram
+  }
 
-    let (lower, higher) = size?;
+  pub fn point_map(&mut self) -> Option<PointMap> {
+    let (lower, higher) = self.size()?;
 
     let mut map: PointMap = Vec::new();
 
@@ -99,22 +170,42 @@ impl VisualObject {
     Some(map)
   }
 
-  pub fn peeled_map(&mut self) -> Option<PointMap> {
-    let map = self.point_map()?;
-    let (lower, higher) = self.size()?;
+  /// Returns the boundary points of the object ordered clockwise.
+  pub fn contour(&mut self) -> Vec<Point> {
+    let (lower, _) = match self.size() {
+      None => return Vec::new(),
+      Some(size) => size,
+    };
 
-    let mut peeled_map: PointMap = Vec::new();
-    for y in 0..(higher.y - lower.y) {
-      let mut row: Vec<bool> = Vec::new();
+    match self.point_map() {
+      None => Vec::new(),
+      Some(map) => trace_contour(&map).into_iter().map(|point| point + lower).collect(),
+    }
+  }
 
-      for x in 0..(higher.x - lower.x) {
-        row.push(is_neighbourhood_highlighted(&map, x, y));
-      }
+  /// Returns the outline of the object simplified into a polygon whose edges
+  /// are at most epsilon cells away from the boundary points.
+  pub fn polygon(&mut self, epsilon: f32) -> Vec<Point> {
+    simplify_polygon(&self.contour(), epsilon)
+  }
 
-      peeled_map.push(row);
-    }
+  /// Returns the smallest convex polygon that contains all points of the object.
+  pub fn convex_hull(&self) -> Vec<Point> {
+    convex_hull(&self.points)
+  }
 
-    Some(peeled_map)
+  /// Returns the rectangle of the smallest area that contains the object. Its
+  /// sides and their ratio do not depend on the rotation of the object.
+  pub fn min_area_rect(&self) -> Option<RotatedRect> {
+    min_area_rect(&self.convex_hull())
+  }
+
+  /// Removes the outer layer of the object. Every point that does not have all
+  /// of its neighbours highlighted is set to off.
+  pub fn peeled_map(&mut self) -> Option<PointMap> {
+    let map = self.point_map()?;
+
+    Some(erode(&map, &StructuringElement::Square(1)))
   }
 
 }
@@ -123,23 +214,69 @@ impl fmt::Debug for VisualObject {
 
   fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
     formatter.write_fmt(
-      format_args!("VisualObject | {} points", self.points.len())
+      format_args!(
+        "VisualObject | {} points, {} children",
+        self.points.len(),
+        self.children.len(),
+      )
     )
   }
 
 }
 
-/// Calculates the mean heat in Moore neighbourhood of a cell at given location.
-fn is_neighbourhood_highlighted(map: &PointMap, x: u32, y: u32) -> bool {
-  let x: isize = x as isize;
-  let y: isize = y as isize;
-
-  pixel_value(map, x - 1, y - 1, false) &&
-  pixel_value(map, x - 1, y + 1, false) &&
-  pixel_value(map, x + 1, y - 1, false) &&
-  pixel_value(map, x + 1, y + 1, false) &&
-  pixel_value(map, x, y - 1, false) &&
-  pixel_value(map, x - 1, y, false) &&
-  pixel_value(map, x + 1, y, false) &&
-  pixel_value(map, x, y + 1, false)
+/// Writes a CSV with a row for each leaf object in the order its crop is
+/// saved in. The row holds the bounding box of the object in cells, how many
+/// cells it covers, how many corners its polygon has, the centre, length,
+/// breadth and angle of its rotated rectangle and its edge orientations if
+/// they were counted.
+pub fn save_objects<P: AsRef<Path>>(
+  objects: &mut [VisualObject],
+  path: P,
+) -> Result<(), SensesError> {
+  let mut csv: String = String::from(
+    "highlight,left,top,right,bottom,cells,corners,center_x,center_y,length,breadth,angle"
+  );
+  for orientation in 0..ORIENTATIONS {
+    csv.push_str(&format!(",orientation_{}", orientation));
+  }
+  csv.push('\n');
+
+  // Empty objects have no crop either.
+  let leaves = objects.iter_mut()
+    .flat_map(|object| object.leaves_mut())
+    .filter(|object| !object.points.is_empty());
+
+  for (index, object) in leaves.enumerate() {
+    let reference = object.reference;
+    let (lower, higher) = object.size().unwrap();
+    let rect = object.min_area_rect().unwrap();
+
+    csv.push_str(&format!(
+      "{},{},{},{},{},{},{},{},{},{},{},{}",
+      index,
+      lower.x + reference.x,
+      lower.y + reference.y,
+      higher.x + reference.x,
+      higher.y + reference.y,
+      object.points.len(),
+      object.polygon(POLYGON_EPSILON).len(),
+      rect.center.0 + reference.x as f32,
+      rect.center.1 + reference.y as f32,
+      rect.width,
+      rect.height,
+      rect.angle,
+    ));
+
+    for orientation in 0..ORIENTATIONS {
+      match object.orientations {
+        Some(counts) => csv.push_str(&format!(",{}", counts[orientation])),
+        None => csv.push(','),
+      }
+    }
+    csv.push('\n');
+  }
+
+  fs::write(path, csv)?;
+
+  Ok(())
 }
@@ -0,0 +1,136 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use senses::visual::helpers::pixel_value;
+use senses::visual::morphology::distance_transform;
+
+type PointMap = Vec<Vec<bool>>;
+
+/// Points whose distance from the border of the object is at least this
+/// fraction of the largest distance within the object are cores of its parts.
+const MARKER_LEVEL: f32 = 0.6_f32;
+
+/// Splits the object in the map along its narrowest necks without removing any
+/// of its points. Distance of each point to the border of the object is
+/// calculated first. The thick cores of the object, which are far from the
+/// border, are used as markers. Markers are then flooded from the points
+/// furthest from the border downhill until all points of the object belong to
+/// one of the markers. The basins of different markers meet at the necks.
+///
+/// Returns a map of the same dimensions for each basin. Should the object have
+/// only one core, a single map equal to the input is returned.
+pub fn watershed(map: &PointMap) -> Vec<PointMap> {
+  let distances = distance_transform(map);
+  let max_distance = distances.iter()
+    .flat_map(|row| row.iter())
+    .fold(0_f32, |max, distance| max.max(*distance));
+
+  // Each point is given the index of the basin it belongs to.
+  let mut labels: Vec<Vec<Option<usize>>> = map.iter()
+    .map(|row| vec!(None; row.len()))
+    .collect();
+  let basins = label_markers(&distances, max_distance * MARKER_LEVEL, &mut labels);
+
+  // Queue of points to be flooded ordered by their distance from the border.
+  // Order of insertion decides between points of the same distance. Each
+  // point carries the label of the basin whose front reached it.
+  let mut queue: BinaryHeap<(u32, Reverse<usize>, usize, usize, usize)> = BinaryHeap::new();
+  let mut order: usize = 0;
+
+  let mut enqueue_neighbours = |
+    queue: &mut BinaryHeap<(u32, Reverse<usize>, usize, usize, usize)>,
+    labels: &Vec<Vec<Option<usize>>>,
+    label: usize,
+    x: usize,
+    y: usize,
+  | {
+    for (neighbour_x, neighbour_y) in moore_neighbourhood(x, y) {
+      if pixel_value(map, neighbour_x, neighbour_y, false)
+        && labels[neighbour_y as usize][neighbour_x as usize].is_none() {
+        let distance = distances[neighbour_y as usize][neighbour_x as usize];
+
+        order += 1;
+        queue.push((
+          (distance * 1000_f32) as u32,
+          Reverse(order),
+          label,
+          neighbour_x as usize,
+          neighbour_y as usize,
+        ));
+      }
+    }
+  };
+
+  for (y, row) in labels.iter().enumerate() {
+    for (x, label) in row.iter().enumerate() {
+      if let Some(label) = *label {
+        enqueue_neighbours(&mut queue, &labels, label, x, y);
+      }
+    }
+  }
+
+  // Each point takes the label of the basin that reached it first.
+  while let Some((_, _, label, x, y)) = queue.pop() {
+    if labels[y][x].is_some() {
+      continue;
+    }
+
+    labels[y][x] = Some(label);
+    enqueue_neighbours(&mut queue, &labels, label, x, y);
+  }
+
+  (0..basins).map(|basin| labels.iter().map(
+    |row| row.iter().map(|label| *label == Some(basin)).collect()
+  ).collect()).collect()
+}
+
+/// Labels connected groups of points whose distance from the border is at
+/// least given level. Returns the number of labels.
+fn label_markers(
+  distances: &[Vec<f32>],
+  level: f32,
+  labels: &mut [Vec<Option<usize>>],
+) -> usize {
+  let mut markers: usize = 0;
+
+  for y in 0..distances.len() {
+    for x in 0..distances[y].len() {
+      if distances[y][x] == 0_f32 || distances[y][x] < level || labels[y][x].is_some() {
+        continue;
+      }
+
+      // Flood fills the marker with an explicit stack.
+      let mut stack: Vec<(usize, usize)> = vec!((x, y));
+      labels[y][x] = Some(markers);
+
+      while let Some((point_x, point_y)) = stack.pop() {
+        for (neighbour_x, neighbour_y) in moore_neighbourhood(point_x, point_y) {
+          let distance = pixel_value(distances, neighbour_x, neighbour_y, 0_f32);
+
+          if distance == 0_f32 || distance < level {
+            continue;
+          }
+
+          let (neighbour_x, neighbour_y) = (neighbour_x as usize, neighbour_y as usize);
+          if labels[neighbour_y][neighbour_x].is_none() {
+            labels[neighbour_y][neighbour_x] = Some(markers);
+            stack.push((neighbour_x, neighbour_y));
+          }
+        }
+      }
+
+      markers += 1;
+    }
+  }
+
+  markers
+}
+
+/// Lists positions of the Moore neighbourhood of a point. Positions may lie
+/// outside of the map.
+fn moore_neighbourhood(x: usize, y: usize) -> impl Iterator<Item = (isize, isize)> {
+  let (x, y) = (x as isize, y as isize);
+
+  ((y - 1)..(y + 2))
+    .flat_map(move |neighbour_y| ((x - 1)..(x + 2)).map(move |neighbour_x| (neighbour_x, neighbour_y)))
+    .filter(move |neighbour| *neighbour != (x, y))
+}
@@ -0,0 +1,59 @@
+//! Tests of the watershed splitter on touching discs, whose neck is where the
+//! split has to be.
+
+use senses::visual::morphology::count_highlighted;
+use senses::visual::watershed::watershed;
+
+type PointMap = Vec<Vec<bool>>;
+
+/// Map of given dimensions with discs of given centres and radius.
+fn discs(width: usize, height: usize, centres: &[(f32, f32)], radius: f32) -> PointMap {
+  (0..height).map(|y| (0..width).map(|x| {
+    centres.iter().any(|(centre_x, centre_y)| {
+      (x as f32 - centre_x).powi(2) + (y as f32 - centre_y).powi(2) <= radius * radius
+    })
+  }).collect()).collect()
+}
+
+#[test]
+fn touching_discs_split_into_two() {
+  let map = discs(30, 17, &[(8_f32, 8_f32), (20_f32, 8_f32)], 6.5_f32);
+  let basins = watershed(&map);
+
+  assert_eq!(basins.len(), 2);
+
+  // Basins partition the object.
+  for y in 0..map.len() {
+    for x in 0..map[y].len() {
+      let owners = basins.iter().filter(|basin| basin[y][x]).count();
+      assert_eq!(owners, if map[y][x] { 1 } else { 0 }, "point {}x{}", x, y);
+    }
+  }
+
+  // Each disc is one basin and the split is at the neck in the middle.
+  let (left, right) = if basins[0][8][8] { (&basins[0], &basins[1]) } else { (&basins[1], &basins[0]) };
+  assert!(left[8][8] && right[8][20]);
+
+  for y in 0..map.len() {
+    for x in 0..map[y].len() {
+      if left[y][x] {
+        assert!(x <= 14, "left basin reaches {}x{}", x, y);
+      }
+      if right[y][x] {
+        assert!(x >= 14, "right basin reaches {}x{}", x, y);
+      }
+    }
+  }
+
+  // Discs are of the same size, so are their basins up to the points on the
+  // neck which go to either of them.
+  let difference = count_highlighted(left) as isize - count_highlighted(right) as isize;
+  assert!(difference.abs() <= 10, "basins differ by {} points", difference);
+}
+
+#[test]
+fn single_disc_is_not_split() {
+  let map = discs(17, 17, &[(8_f32, 8_f32)], 6.5_f32);
+
+  assert_eq!(watershed(&map), vec!(map));
+}
@@ -2,14 +2,27 @@ mod neuron;
 mod point;
 mod table;
 
-use self::neuron::Neuron;
-use self::point::Point;
+#[cfg(test)]
+mod som_tests;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub use self::neuron::Neuron;
+pub use self::point::Point;
 use self::table::Table;
 
 pub struct SelfOrganizingMap {
-    /// Root node which represents the whole search space.
+    /// Root node which represents the whole search space. It holds indices
+    /// into the neurons.
     root: Table,
 
+    /// Neurons of the map and where they are.
+    neurons: Vec<(Point, Neuron)>,
+
     /// Size of the ideal leaf that will eventually most likely contain only four
     /// elements.
     leaf_size: f64,
@@ -22,6 +35,17 @@ pub struct SelfOrganizingMap {
 
     /// How many leaves have less than 4 items.
     underused_leaves: usize,
+
+    /// How many items does each leaf hold, leaves are squares of the leaf size
+    /// indexed by their bottom left corner.
+    leaves: HashMap<(i64, i64), usize>,
+
+    /// How many inputs has the map been trained on.
+    trained: usize,
+
+    /// Mean distance between the inputs and the weights of their best matching
+    /// neurons.
+    error: f64,
 }
 
 impl SelfOrganizingMap {
@@ -36,9 +60,9 @@ impl SelfOrganizingMap {
 
         for (index, (point, _)) in items.iter().enumerate() {
             average_x += (point.x - average_x) / (index + 1) as f64;
-            average_y += (point.x - average_y) / (index + 1) as f64;
+            average_y += (point.y - average_y) / (index + 1) as f64;
 
-            let mut neighbours: Vec<Option<(f64, Point)>> = vec![None, None, None];
+            let mut neighbours: Vec<(f64, Point)> = Vec::with_capacity(4);
 
             // This iterates through all other points, making this operation extremely expensive.
             for (another_index, (another_point, _)) in items.iter().enumerate() {
@@ -47,18 +71,12 @@ impl SelfOrganizingMap {
                 }
 
                 // Calculates the distance between the pair of points.
-                let mut distance = point.distance_to(another_point);
+                let distance = point.distance_to(another_point);
 
                 // Updates the neighbours vec storing only the 3 closest points.
-                for mut neighbour in neighbours.iter_mut() {
-                    if neighbour
-                        .get_or_insert_with(|| (distance, *another_point))
-                        .0
-                        < distance
-                    {
-                        distance = neighbour.replace((distance, *another_point)).unwrap().0;
-                    }
-                }
+                neighbours.push((distance, *another_point));
+                neighbours.sort_by(|a, b| a.0.total_cmp(&b.0));
+                neighbours.truncate(3);
             }
 
             // Size of the square that would include all 4 points.
@@ -69,9 +87,13 @@ impl SelfOrganizingMap {
         let mut som = SelfOrganizingMap {
             leaf_size: average_square,
             root: Table::new(Point::new(average_x, average_y)),
+            neurons: Vec::with_capacity(items.len()),
             items: 0,
             overused_leaves: 0,
             underused_leaves: 0,
+            leaves: HashMap::new(),
+            trained: 0,
+            error: 0_f64,
         };
 
         for item in items {
@@ -81,21 +103,127 @@ impl SelfOrganizingMap {
         som
     }
 
-    pub fn insert(&mut self, item: (Point, Neuron)) {
-        //
+    /// Inserts the neuron at given point. A neuron that already is at the
+    /// point is replaced.
+    pub fn insert(&mut self, (point, neuron): (Point, Neuron)) {
+        if let Some(index) = self.root.at(point) {
+            self.neurons[index] = (point, neuron);
+
+            return;
+        }
+
+        let index = self.neurons.len();
+        if let Some(replaced) = self.root.insert(index, point) {
+            // The points are too close to be told apart.
+            self.neurons[replaced] = (point, neuron);
+
+            return;
+        }
+
+        self.neurons.push((point, neuron));
+        self.items += 1;
+
+        // Updates the statistics of the leaf the neuron falls into.
+        let size = self.leaf_size.max(f64::EPSILON);
+        let leaf = (
+            (point.x / size).floor() as i64,
+            (point.y / size).floor() as i64,
+        );
+        let count = self.leaves.entry(leaf).or_insert(0);
+        *count += 1;
+
+        match *count {
+            1 => self.underused_leaves += 1,
+            4 => self.underused_leaves -= 1,
+            5 => self.overused_leaves += 1,
+            _ => (),
+        }
+    }
+
+    /// How many neurons the map holds. Neurons inserted at a point that is
+    /// already taken replace the neuron there, so it can be fewer than were
+    /// inserted.
+    pub fn items(&self) -> usize {
+        self.items
+    }
+
+    /// Trains the map on the inputs for given number of epochs. The
+    /// neighbourhood shrinks from two leaves to half a leaf and the learning
+    /// rate decays over the epochs, so that the map settles.
+    pub fn fit(&mut self, inputs: &[Vec<f32>], epochs: usize) {
+        for epoch in 0..epochs {
+            let progress = epoch as f64 / epochs.max(2).saturating_sub(1) as f64;
+            let radius = self.leaf_size * (2_f64 - 1.5_f64 * progress);
+            let rate = (0.5_f64 - 0.49_f64 * progress) as f32;
+
+            for input in inputs {
+                self.train(input, radius, rate);
+            }
+        }
+    }
+
+    /// Moves the best matching neuron of the input and the neurons within
+    /// given radius around it towards the input. The further a neuron is from
+    /// the best matching one, the less it moves. Returns the index of the best
+    /// matching neuron, or none if no neuron has as many weights as the input.
+    pub fn train(&mut self, input: &[f32], radius: f64, rate: f32) -> Option<usize> {
+        let (best, distance) = self
+            .neurons
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, neuron))| neuron.weights.len() == input.len())
+            .map(|(index, (_, neuron))| (index, weight_distance(&neuron.weights, input)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))?;
+
+        self.trained += 1;
+        self.error += (distance as f64 - self.error) / self.trained as f64;
+
+        let center = self.neurons[best].0;
+        for index in self.root.around(center, radius) {
+            let (point, ref mut neuron) = self.neurons[index];
+            if neuron.weights.len() != input.len() {
+                continue;
+            }
+
+            // Gaussian neighbourhood which is 1 at the best matching neuron.
+            let spread = 2_f64 * radius.powi(2);
+            let influence = if spread > 0_f64 {
+                (-point.distance_to(&center).powi(2) / spread).exp() as f32
+            } else {
+                1_f32
+            };
+
+            for (weight, value) in neuron.weights.iter_mut().zip(input.iter()) {
+                *weight += rate * influence * (value - *weight);
+            }
+        }
+
+        Some(best)
+    }
+
+    /// Saves the statistics of the map into a file, one `key=value` per line.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+}
+
+impl fmt::Display for SelfOrganizingMap {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(formatter, "items={}", self.items)?;
+        writeln!(formatter, "leaf_size={}", self.leaf_size)?;
+        writeln!(formatter, "overused_leaves={}", self.overused_leaves)?;
+        writeln!(formatter, "underused_leaves={}", self.underused_leaves)?;
+        writeln!(formatter, "trained={}", self.trained)?;
+        writeln!(formatter, "error={}", self.error)
     }
 }
 
 /// Calculates the size of the square needed to fit given points.
-fn square_size(point: Point, neighbours: &Vec<Option<(f64, Point)>>) -> f64 {
+fn square_size(point: Point, neighbours: &[(f64, Point)]) -> f64 {
     // Finds max and min x, y among all neighbours and the point.
     let (min_x, min_y, max_x, max_y) = neighbours.iter().fold(
         (point.x, point.y, point.x, point.y),
-        |(min_x, min_y, max_x, max_y), o| {
-            // We have asserted that there are at least 4 items in the vector,
-            // therefore we can safely unwrap.
-            let (_, neighbour) = o.unwrap();
-
+        |(min_x, min_y, max_x, max_y), (_, neighbour)| {
             (
                 min_x.min(neighbour.x),
                 min_y.min(neighbour.y),
@@ -105,5 +233,15 @@ fn square_size(point: Point, neighbours: &Vec<Option<(f64, Point)>>) -> f64 {
         },
     );
 
-    (max_x - min_x).abs().max(max_y - min_y.abs())
+    (max_x - min_x).abs().max((max_y - min_y).abs())
+}
+
+/// Euclidean distance between the weights of a neuron and an input.
+fn weight_distance(weights: &[f32], input: &[f32]) -> f32 {
+    weights
+        .iter()
+        .zip(input.iter())
+        .map(|(weight, value)| (weight - value).powi(2))
+        .sum::<f32>()
+        .sqrt()
 }
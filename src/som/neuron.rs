@@ -1,4 +1,12 @@
-
+/// Unit of the map. Its weights are the features of the percept it stands for,
+/// e.g. the MFCCs of an audio frame.
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Neuron {
-  //
+  pub weights: Vec<f32>,
+}
+
+impl Neuron {
+  pub fn new(weights: Vec<f32>) -> Neuron {
+    Neuron { weights }
+  }
 }
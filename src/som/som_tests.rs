@@ -0,0 +1,107 @@
+//! Tests of the quad tree of neurons and of training the map.
+
+use super::table::Table;
+use super::{Neuron, Point, SelfOrganizingMap};
+
+/// Map of a 4 by 4 grid of neurons with one weight.
+fn grid(weight: f32) -> SelfOrganizingMap {
+    let items = (0..16)
+        .map(|index| {
+            let point = Point::new((index % 4) as f64, (index / 4) as f64);
+
+            (point, Neuron::new(vec![weight]))
+        })
+        .collect();
+
+    SelfOrganizingMap::new(items)
+}
+
+#[test]
+fn table_finds_neurons_at_points_and_around_them() {
+    let mut table = Table::new(Point::new(0_f64, 0_f64));
+    let points = [
+        (0_f64, 0_f64),
+        (1_f64, 1_f64),
+        (-2_f64, 1_f64),
+        (5_f64, -5_f64),
+        (1.5_f64, 1_f64),
+    ];
+
+    for (index, (x, y)) in points.iter().enumerate() {
+        assert_eq!(table.insert(index, Point::new(*x, *y)), None);
+    }
+
+    for (index, (x, y)) in points.iter().enumerate() {
+        assert_eq!(table.at(Point::new(*x, *y)), Some(index));
+    }
+    assert_eq!(table.at(Point::new(2_f64, 2_f64)), None);
+
+    let mut around = table.around(Point::new(1_f64, 1_f64), 1.5_f64);
+    around.sort();
+    assert_eq!(around, vec![0, 1, 4]);
+
+    // Inserting at an occupied point replaces the neuron.
+    assert_eq!(table.insert(7, Point::new(1_f64, 1_f64)), Some(1));
+    assert_eq!(table.at(Point::new(1_f64, 1_f64)), Some(7));
+}
+
+#[test]
+fn leaves_of_a_grid_fit_neighbouring_points() {
+    let map = grid(0_f32);
+
+    // Three closest neighbours of a point fit a square of size 1 in the
+    // corners and of size 2 elsewhere.
+    assert_eq!(map.items, 16);
+    assert!(
+        map.leaf_size >= 1_f64 && map.leaf_size <= 2_f64,
+        "{}",
+        map.leaf_size
+    );
+    assert_eq!(map.leaves.values().sum::<usize>(), 16);
+}
+
+#[test]
+fn training_pulls_the_neighbourhood_towards_the_input() {
+    let mut map = grid(0_f32);
+    map.neurons[5].1 = Neuron::new(vec![0.9_f32]);
+
+    assert_eq!(map.train(&[1_f32], 1_f64, 0.5_f32), Some(5));
+
+    let weight = |x: f64, y: f64| {
+        let index = map.root.at(Point::new(x, y)).unwrap();
+
+        map.neurons[index].1.weights[0]
+    };
+
+    // Best matching neuron moves by the rate, its neighbours by less and the
+    // rest of the map stays.
+    assert!((weight(1_f64, 1_f64) - 0.95_f32).abs() < 1e-6);
+    assert!(weight(2_f64, 1_f64) > 0_f32 && weight(2_f64, 1_f64) < 0.5_f32);
+    assert_eq!(weight(3_f64, 3_f64), 0_f32);
+
+    assert_eq!(map.trained, 1);
+    assert!((map.error - 0.1_f64).abs() < 1e-6);
+}
+
+#[test]
+fn inputs_of_other_length_are_not_trained_on() {
+    let mut map = grid(0_f32);
+
+    assert_eq!(map.train(&[1_f32, 1_f32], 1_f64, 0.5_f32), None);
+    assert_eq!(map.trained, 0);
+}
+
+#[test]
+fn neurons_at_the_same_point_are_counted_once() {
+    let mut items: Vec<(Point, Neuron)> = (0..4)
+        .map(|index| (Point::new(index as f64, 0_f64), Neuron::new(vec![0_f32])))
+        .collect();
+    items.push((Point::new(2_f64, 0_f64), Neuron::new(vec![1_f32])));
+
+    let map = SelfOrganizingMap::new(items);
+
+    // The later neuron replaces the earlier one at its point.
+    assert_eq!(map.items(), 4);
+    let index = map.root.at(Point::new(2_f64, 0_f64)).unwrap();
+    assert_eq!(map.neurons[index].1.weights, vec![1_f32]);
+}
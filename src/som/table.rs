@@ -1,7 +1,9 @@
-use super::neuron::Neuron;
+use std::mem;
+
 use super::point::Point;
-use std::rc::Rc;
 
+/// Quad tree of the neurons of the map, which makes looking up the neurons
+/// close to a point cheaper than going through all of them.
 pub enum Table {
     /// Leaf only needs to know where is it in the space and what neuron it represents.
     Leaf(Point, usize),
@@ -10,82 +12,100 @@ pub enum Table {
         split: Point,
 
         /// Each table is split into four quadrants.
-        quadrants: [Option<Rc<Table>>; 4],
-
-        /// Whether the table is limited by a bottom left point.
-        lower_bound: Option<Point>,
-
-        /// Whether the table is limited by a top right point.
-        upper_bound: Option<Point>,
+        quadrants: [Option<Box<Table>>; 4],
     },
 }
 
 impl Table {
     /// Builds new empty table with point that splits it into 4 quadrants.
-    pub fn new_node(split: Point) -> Table {
+    pub fn new(split: Point) -> Table {
         Table::Node {
             split,
-            neuron: None,
-            lower_bound: None,
-            upper_bound: None,
             quadrants: [None, None, None, None],
         }
     }
 
-    pub fn insert(&mut self, neuron: usize, at: Point) {
-        let quadrant = which_quadrant(self.split, at);
+    /// Inserts the neuron at given point. If there already is a neuron at the
+    /// point, it is replaced and returned.
+    pub fn insert(&mut self, neuron: usize, at: Point) -> Option<usize> {
+        match self {
+            Table::Leaf(point, existing) => {
+                let split = Point::new((point.x + at.x) / 2_f64, (point.y + at.y) / 2_f64);
 
-        if let Some(neuron) = self.neuron {
-            self.neuron = None;
+                // Points that cannot be told apart are the same point.
+                if which_quadrant(split, *point) == which_quadrant(split, at) {
+                    *point = at;
 
-            return;
-        }
+                    return Some(mem::replace(existing, neuron));
+                }
 
-        let boxed: &Option<Rc<Table>> = &self.quadrants[quadrant];
+                // Leaf becomes a node with the old and the new neuron in
+                // different quadrants.
+                let mut node = Table::new(split);
+                node.insert(*existing, *point);
+                node.insert(neuron, at);
+                *self = node;
 
-        match boxed {
-            Some(cell) => cell.insert(neuron, at),
-            None => {
-                let mut quadrant_table = Table::new(at);
-                quadrant_table.neuron = Some(neuron);
+                None
             }
+            Table::Node { split, quadrants } => match quadrants[which_quadrant(*split, at)] {
+                Some(ref mut table) => table.insert(neuron, at),
+                ref mut quadrant => {
+                    *quadrant = Some(Box::new(Table::Leaf(at, neuron)));
+
+                    None
+                }
+            },
         }
-
-        // if neuron already exists
-        //   create two new tables with both new and old neurons
-        //   remove neuron from this table
-        //   push new tables into this table with appropriate bounds
-        // else if quadrant table doesnt exist
-        //   create new table with the neuron and assign it as the quadrant
-        // else
-        //   calt insert on the quadrant table
     }
 
     /// Retrieves neuron at given point if exists.
     pub fn at(&self, point: Point) -> Option<usize> {
-        // If this table has a point, it is a leaf node and split is a point which the neuron
-        // lies on.
-        if let Some(neuron) = self.neuron {
-            return if point == self.split {
-                Some(neuron)
-            } else {
-                None
-            };
+        match self {
+            Table::Leaf(at, neuron) => {
+                if *at == point {
+                    Some(*neuron)
+                } else {
+                    None
+                }
+            }
+            Table::Node { split, quadrants } => quadrants[which_quadrant(*split, point)]
+                .as_ref()
+                .and_then(|table| table.at(point)),
         }
+    }
 
-        let quadrant = which_quadrant(self.split, point);
-
-        let boxed: &Option<Rc<Table>> = &self.quadrants[quadrant];
+    /// Finds all neurons in given circle.
+    pub fn around(&self, center: Point, radius: f64) -> Vec<usize> {
+        let mut neurons = Vec::new();
+        self.collect_around(center, radius, &mut neurons);
 
-        match boxed {
-            Some(cell) => cell.at(point),
-            None => None,
-        }
+        neurons
     }
 
-    /// Finds all neurons in given circle.
-    pub fn around(&self, center: Point, radius: f64) -> Option<usize> {
-        None
+    fn collect_around(&self, center: Point, radius: f64, neurons: &mut Vec<usize>) {
+        match self {
+            Table::Leaf(at, neuron) => {
+                if at.distance_to(&center) <= radius {
+                    neurons.push(*neuron);
+                }
+            }
+            Table::Node { split, quadrants } => {
+                // Quadrants on the other side of the split than the circle
+                // are skipped.
+                let right = center.x + radius >= split.x;
+                let left = center.x - radius <= split.x;
+                let top = center.y + radius >= split.y;
+                let bottom = center.y - radius < split.y;
+                let reached = [top && right, top && left, bottom && left, bottom && right];
+
+                for (quadrant, reached) in quadrants.iter().zip(reached.iter()) {
+                    if let (Some(table), true) = (quadrant, reached) {
+                        table.collect_around(center, radius, neurons);
+                    }
+                }
+            }
+        }
     }
 }
 
@@ -97,17 +117,15 @@ impl Table {
 ///   point.y < table.y    | point.y < table.y
 ///   point.x <= table.x   | point.x > table.x
 fn which_quadrant(center: Point, point: Point) -> usize {
-    if point.y >= self.split.y {
-        if point.x >= self.split.x {
+    if point.y >= center.y {
+        if point.x >= center.x {
             0
         } else {
             1
         }
+    } else if point.x <= center.x {
+        2
     } else {
-        if point.x <= self.split.x {
-            2
-        } else {
-            3
-        }
-    };
+        3
+    }
 }